@@ -0,0 +1,87 @@
+//! 対局の進行状態・操作・イベント
+
+use crate::game::{Claim, HandResult};
+use crate::tile::{Honor, Tile};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 対局の進行状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    Dealing,         // 配牌中
+    AwaitingDraw,    // 手番プレイヤーのツモ待ち
+    AwaitingDiscard, // 手番プレイヤーの打牌待ち（ツモ・鳴きの後）
+    ClaimWindow,     // 打牌に対する鳴き・ロンの受付中
+    HandOver,        // 局の終了
+    GameOver,        // 対局の終了
+}
+
+/// プレイヤーの操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Draw { player: usize },
+    Discard { player: usize, tile: Tile },
+    Riichi { player: usize }, // リーチ宣言（続けて宣言牌を打牌する）
+    Tsumo { player: usize },
+    Ankan { player: usize, tile: Tile },
+    Kakan { player: usize, tile: Tile },   // ポンした面子に4枚目を加える
+    KyuushuKyuuhai { player: usize },      // 九種九牌で流局を宣言
+    Kita { player: usize },                // 三人麻雀で北を抜きドラにする
+    Claim { player: usize, claim: Claim }, // 打牌に対する応答（パスを含む）
+}
+
+/// 操作の結果として起きた出来事（牌譜にもこのまま記録する）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    Dealt { player: usize, tiles: Vec<Tile> }, // 配牌（親の14枚目は Drew）
+    Drew { player: usize, tile: Tile },
+    Discarded { player: usize, tile: Tile, tsumogiri: bool, riichi: bool }, // ツモ切り・リーチ宣言牌か
+    RiichiDeclared { player: usize },
+    ClaimWindowOpened { discarder: usize, tile: Tile, pending: Vec<usize> },
+    Called { player: usize, claim: Claim, tile: Tile }, // チー・ポン・明槓
+    Ankan { player: usize, tile: Tile },
+    Kakan { player: usize, tile: Tile },
+    Kita { player: usize },
+    DoraRevealed { indicator: Tile },
+    HandEnded(HandResult),
+    ScoresChanged { deltas: Vec<i32> }, // 席順の点数移動（和了・流局の精算、リーチ棒）
+    HandStarted { round_wind: Honor, round: u32, honba: u32, dealer: usize },
+}
+
+/// 不正な操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleError {
+    WrongPhase(Phase), // 現在のフェーズでは行えない操作
+    NotYourTurn,
+    TileNotInHand,
+    WallEmpty,
+    CannotRiichi,
+    CannotWin, // 和了形でない・役がない・フリテン
+    CannotKan,
+    InvalidClaim,
+    CannotAbort,  // 途中流局の条件を満たしていない
+    Kuikae,       // 喰い替えになる打牌
+    RiichiLocked, // リーチ後のツモ切り以外の打牌
+    CannotKita,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::WrongPhase(phase) => write!(f, "現在のフェーズ（{:?}）では行えません", phase),
+            RuleError::NotYourTurn => write!(f, "手番ではありません"),
+            RuleError::TileNotInHand => write!(f, "その牌は手牌にありません"),
+            RuleError::WallEmpty => write!(f, "山牌がありません"),
+            RuleError::CannotRiichi => write!(f, "リーチできません"),
+            RuleError::CannotWin => write!(f, "和了できません"),
+            RuleError::CannotKan => write!(f, "カンできません"),
+            RuleError::InvalidClaim => write!(f, "その鳴きはできません"),
+            RuleError::CannotAbort => write!(f, "流局を宣言できません"),
+            RuleError::Kuikae => write!(f, "喰い替えになる牌は打牌できません"),
+            RuleError::RiichiLocked => write!(f, "リーチ後はツモ切りしかできません"),
+            RuleError::CannotKita => write!(f, "北抜きできません"),
+        }
+    }
+}
+
+impl std::error::Error for RuleError {}
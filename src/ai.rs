@@ -1,7 +1,7 @@
 use crate::tile::Tile;
 use crate::hand::Hand;
+use crate::rng::{random_seed, seeded_rng, GameRng};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AiLevel {
@@ -13,36 +13,50 @@ pub enum AiLevel {
 
 pub struct AiEngine {
     level: AiLevel,
+    rng: GameRng,
 }
 
 impl AiEngine {
     pub fn new(level: AiLevel) -> Self {
-        Self { level }
+        Self::with_seed(level, random_seed())
+    }
+
+    /// シード値を指定して作る（ランダム打牌が再現される）
+    pub fn with_seed(level: AiLevel, seed: u64) -> Self {
+        Self::with_rng(level, seeded_rng(seed))
+    }
+
+    /// 乱数生成器を差し替えて作る
+    pub fn with_rng(level: AiLevel, rng: GameRng) -> Self {
+        Self { level, rng }
     }
 
     /// AIが打牌する牌を選択
-    pub fn select_discard(&self, hand: &Hand) -> Option<Tile> {
-        let tiles = hand.get_tiles();
-        if tiles.is_empty() {
+    pub fn select_discard(&mut self, hand: &Hand) -> Option<Tile> {
+        self.select_legal_discard(hand, hand.get_tiles())
+    }
+
+    /// 打牌できる牌（Game::legal_discards）の中からAIが打牌する牌を選択
+    pub fn select_legal_discard(&mut self, hand: &Hand, legal: &[Tile]) -> Option<Tile> {
+        if legal.is_empty() {
             return None;
         }
 
         match self.level {
-            AiLevel::Random => self.select_random(tiles),
-            AiLevel::Simple => self.select_simple(tiles),
-            AiLevel::Intermediate => self.select_intermediate(hand),
-            AiLevel::Advanced => self.select_advanced(hand),
+            AiLevel::Random => self.select_random(legal),
+            AiLevel::Simple => self.select_simple(hand.get_tiles(), legal),
+            AiLevel::Intermediate => self.select_intermediate(hand, legal),
+            AiLevel::Advanced => self.select_advanced(hand, legal),
         }
     }
 
     /// レベル1: ランダムに打牌を選択
-    fn select_random(&self, tiles: &[Tile]) -> Option<Tile> {
-        let mut rng = thread_rng();
-        tiles.choose(&mut rng).copied()
+    fn select_random(&mut self, tiles: &[Tile]) -> Option<Tile> {
+        tiles.choose(&mut self.rng).copied()
     }
 
     /// レベル2: 孤立牌を優先的に打牌
-    fn select_simple(&self, tiles: &[Tile]) -> Option<Tile> {
+    fn select_simple(&self, tiles: &[Tile], candidates: &[Tile]) -> Option<Tile> {
         use crate::tile::TileType;
         use std::collections::HashMap;
 
@@ -53,7 +67,7 @@ impl AiEngine {
         }
 
         // 1. 字牌で孤立しているものを優先
-        for tile in tiles {
+        for tile in candidates {
             if let TileType::Honor(_) = tile.tile_type {
                 if tile_counts.get(tile).copied().unwrap_or(0) == 1 {
                     return Some(*tile);
@@ -62,7 +76,7 @@ impl AiEngine {
         }
 
         // 2. 数牌で孤立しているものを選択
-        for tile in tiles {
+        for tile in candidates {
             if let TileType::Number { suit, value } = tile.tile_type {
                 // 前後の牌がないか確認
                 let has_prev = if value > 1 {
@@ -87,7 +101,7 @@ impl AiEngine {
         }
 
         // 3. 端牌（1, 9）を優先
-        for tile in tiles {
+        for tile in candidates {
             if let TileType::Number { value, .. } = tile.tile_type {
                 if value == 1 || value == 9 {
                     return Some(*tile);
@@ -96,22 +110,16 @@ impl AiEngine {
         }
 
         // 4. どれも該当しない場合は最初の牌
-        tiles.first().copied()
+        candidates.first().copied()
     }
 
     /// レベル3: シャンテン数を考慮した打牌選択
-    fn select_intermediate(&self, hand: &Hand) -> Option<Tile> {
-        let tiles = hand.get_tiles();
-        if tiles.is_empty() {
-            return None;
-        }
-
-        let current_shanten = hand.shanten();
-        let mut best_tile = tiles[0];
+    fn select_intermediate(&self, hand: &Hand, candidates: &[Tile]) -> Option<Tile> {
+        let mut best_tile = *candidates.first()?;
         let mut best_shanten = 100;
 
         // 各牌を打牌した場合のシャンテン数を計算
-        for &tile in tiles {
+        for &tile in candidates {
             let mut test_hand = hand.clone();
             if test_hand.remove_tile(&tile) {
                 let shanten = test_hand.shanten();
@@ -133,10 +141,10 @@ impl AiEngine {
     }
 
     /// レベル4: 期待値計算ベース（未実装）
-    fn select_advanced(&self, hand: &Hand) -> Option<Tile> {
+    fn select_advanced(&self, hand: &Hand, candidates: &[Tile]) -> Option<Tile> {
         // TODO: より高度な期待値計算を実装
         // 暫定的にレベル3と同じ
-        self.select_intermediate(hand)
+        self.select_intermediate(hand, candidates)
     }
 
     /// 牌の有用性を比較（より有用でない方がtrue）
@@ -172,7 +180,7 @@ mod tests {
 
     #[test]
     fn test_ai_random() {
-        let ai = AiEngine::new(AiLevel::Random);
+        let mut ai = AiEngine::new(AiLevel::Random);
         let mut hand = Hand::new();
         hand.add_tile(Tile::new_number(Suit::Man, 1, false));
         hand.add_tile(Tile::new_number(Suit::Man, 2, false));
@@ -184,7 +192,7 @@ mod tests {
 
     #[test]
     fn test_ai_simple() {
-        let ai = AiEngine::new(AiLevel::Simple);
+        let mut ai = AiEngine::new(AiLevel::Simple);
         let mut hand = Hand::new();
 
         // 孤立字牌を追加
@@ -200,7 +208,7 @@ mod tests {
 
     #[test]
     fn test_ai_intermediate() {
-        let ai = AiEngine::new(AiLevel::Intermediate);
+        let mut ai = AiEngine::new(AiLevel::Intermediate);
         let mut hand = Hand::new();
 
         hand.add_tile(Tile::new_number(Suit::Man, 1, false));
@@ -211,4 +219,34 @@ mod tests {
         let discard = ai.select_discard(&hand);
         assert!(discard.is_some());
     }
+
+    #[test]
+    fn test_ai_respects_legal_discards() {
+        let mut ai = AiEngine::new(AiLevel::Intermediate);
+        let mut hand = Hand::new();
+        for tile in Tile::parse_tiles("123m 5p to").unwrap() {
+            hand.add_tile(tile);
+        }
+
+        // 字牌を切りたい形でも、打牌できる牌の中から選ぶ
+        let legal = Tile::parse_tiles("1m 5p").unwrap();
+        let discard = ai.select_legal_discard(&hand, &legal).unwrap();
+        assert!(legal.contains(&discard));
+        assert_eq!(ai.select_legal_discard(&hand, &[]), None);
+    }
+
+    #[test]
+    fn test_ai_random_is_reproducible_with_seed() {
+        let tiles = Tile::parse_tiles("123456789m 19p to").unwrap();
+        let mut hand = Hand::new();
+        for tile in &tiles {
+            hand.add_tile(*tile);
+        }
+
+        let mut a = AiEngine::with_seed(AiLevel::Random, 7);
+        let mut b = AiEngine::with_seed(AiLevel::Random, 7);
+        let picks_a: Vec<Option<Tile>> = (0..10).map(|_| a.select_discard(&hand)).collect();
+        let picks_b: Vec<Option<Tile>> = (0..10).map(|_| b.select_discard(&hand)).collect();
+        assert_eq!(picks_a, picks_b);
+    }
 }
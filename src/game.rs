@@ -1,62 +1,315 @@
+use crate::action::{Action, Event, Phase, RuleError};
+use crate::game_log::GameLog;
+use crate::hand::{Hand, KanType, Meld, MeldType, Ukeire};
 use crate::player::Player;
+use crate::rules::{KanDoraTiming, MultipleRon, RuleSet};
+use crate::scoring::{Payment, ScoringEngine, ScoringResult, Transfer, WinContext, Yaku};
+use crate::standings::MatchResult;
 use crate::tile::{Tile, TileType, Suit, Honor};
+use crate::wall::{Wall, SANMA_RINSHAN_COUNT};
+use crate::rng::{random_seed, roll_dice, seeded_rng, GameRng};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+
+/// 和了1件分の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WinResult {
+    pub winner: usize,
+    pub loser: Option<usize>, // ツモならNone
+    pub winning_tile: Tile,
+    pub scoring: ScoringResult,
+    pub payment: Payment,
+}
+
+/// 荒牌流局の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawResult {
+    pub tenpai: Vec<usize>,
+    pub nagashi: Vec<usize>,       // 流し満貫（成立時はノーテン罰符なし）
+    pub transfers: Vec<Transfer>, // ノーテン罰符または流し満貫の支払い
+}
+
+impl DrawResult {
+    /// 指定プレイヤーの収支
+    pub fn delta(&self, player: usize) -> i32 {
+        self.transfers
+            .iter()
+            .map(|transfer| {
+                if transfer.from == player {
+                    -(transfer.points as i32)
+                } else if transfer.to == player {
+                    transfer.points as i32
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+}
+
+/// 途中流局の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbortReason {
+    KyuushuKyuuhai(usize), // 九種九牌（宣言したプレイヤー）
+    SuufonRenda,           // 四風連打
+    SuuchaRiichi,          // 四家立直
+    Suukaikan,             // 四開槓
+    Sanchahou,             // 三家和
+}
+
+/// 局の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandResult {
+    Win(Vec<WinResult>), // ダブロンは放銃者の下家から近い順
+    Draw(DrawResult),    // 荒牌流局
+    Abort(AbortReason),  // 途中流局（親の連荘）
+}
+
+impl HandResult {
+    /// 席順の点数移動（供託の受け取りを含む）
+    pub fn score_deltas(&self, player_count: usize) -> Vec<i32> {
+        (0..player_count)
+            .map(|i| match self {
+                HandResult::Win(wins) => wins.iter().map(|win| win.payment.delta(i)).sum(),
+                HandResult::Draw(draw) => draw.delta(i),
+                HandResult::Abort(_) => 0,
+            })
+            .collect()
+    }
+}
+
+/// 打牌に対する応答
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Claim {
+    Pass,
+    Chi(usize), // チーのパターン（Game::chi_patterns）
+    Pon,
+    Kan,
+    Ron,
+}
+
+/// 打牌に対する鳴き・ロンの受付状態
+#[derive(Debug, Clone)]
+pub struct ClaimWindow {
+    pub discarder: usize,
+    pub tile: Tile,
+    pub pending: Vec<usize>,         // まだ応答していないプレイヤー
+    pub claims: Vec<(usize, Claim)>, // パス以外の応答
+    pub robbed_kan: Option<KanType>, // 槍槓の受付ならカンの種類（ロンのみ可能）
+}
 
 #[derive(Debug, Clone)]
 pub struct Game {
-    pub players: Vec<Player>,
-    pub wall: Vec<Tile>,
-    pub dora_indicators: Vec<Tile>,
-    pub current_player: usize,
-    pub round: u32,
-    pub dealer: usize,
-    pub last_discard: Option<Tile>,
+    pub(crate) players: Vec<Player>,
+    pub(crate) wall: Wall,
+    pub(crate) current_player: usize,
+    pub(crate) round: u32, // 場の中での局数（1〜4）
+    pub(crate) dealer: usize,
+    pub(crate) honba: u32,         // 積み棒
+    pub(crate) riichi_sticks: u32, // 供託リーチ棒
+    pub(crate) round_wind: Honor,
+    pub(crate) last_discard: Option<Tile>,
+    pub(crate) last_draw: Option<Tile>, // 手番プレイヤーが直前にツモった牌
+    pub(crate) rules: RuleSet,
+    pub(crate) phase: Phase,
+    pub(crate) hand_result: Option<HandResult>,
+    pub(crate) claim_window: Option<ClaimWindow>, // 打牌に対する鳴き・ロンの受付
+    pub(crate) call_made: bool,    // 局中に鳴きがあったか
+    pub(crate) rinshan_draw: bool, // 直前のツモが嶺上牌か
+    pub(crate) pending_kan_dora: usize, // 打牌後にめくる槓ドラの枚数
+    pub(crate) kuikae_tiles: Vec<Tile>, // 鳴いた直後に打牌できない牌（喰い替え）
+    pub(crate) pending_riichi: Option<usize>, // 宣言牌がまだ通っていないリーチ（供託は通ってから）
+    pub(crate) seed: u64,               // 山・サイコロを決めるシード値（牌譜に残す）
+    pub(crate) dice: [u8; 2],           // その局のサイコロの目
+    rng: GameRng,
+    log: GameLog,
 }
 
 impl Game {
-    pub fn new(player_names: Vec<String>) -> Self {
-        assert!(player_names.len() == 4, "Mahjong requires exactly 4 players");
+    pub fn new(player_names: Vec<String>, rules: RuleSet) -> Self {
+        Self::with_seed(player_names, rules, random_seed())
+    }
+
+    /// シード値を指定して対局を作る（同じシード値なら山とサイコロが再現される）
+    pub fn with_seed(player_names: Vec<String>, rules: RuleSet, seed: u64) -> Self {
+        assert!(matches!(rules.players, 3 | 4), "Mahjong requires 3 or 4 players");
+        assert!(player_names.len() == rules.players, "Player names must match the rule's player count");
         
+        let log = GameLog::new(seed, rules.clone(), player_names.clone());
         let players: Vec<Player> = player_names
             .into_iter()
             .enumerate()
             .map(|(i, name)| {
                 let mut player = Player::new(i, name);
-                if i == 0 {
-                    player.is_dealer = true;
-                }
+                player.score = rules.starting_score;
                 player
             })
             .collect();
 
         let mut game = Self {
             players,
-            wall: Vec::new(),
-            dora_indicators: Vec::new(),
+            wall: Wall::default(),
             current_player: 0,
             round: 1,
             dealer: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            round_wind: Honor::Ton,
             last_discard: None,
+            last_draw: None,
+            rules,
+            phase: Phase::Dealing,
+            hand_result: None,
+            claim_window: None,
+            call_made: false,
+            rinshan_draw: false,
+            pending_kan_dora: 0,
+            kuikae_tiles: Vec::new(),
+            pending_riichi: None,
+            seed,
+            dice: [1, 1],
+            rng: seeded_rng(seed),
+            log,
         };
 
-        game.initialize_wall();
-        game.deal_initial_tiles();
+        game.start_hand();
         game
     }
 
+    /// プレイヤーごとのAIに渡すシード値（対局のシード値から決まる）
+    pub fn ai_seed(&self, player: usize) -> u64 {
+        self.seed.wrapping_add(player as u64 + 1)
+    }
+
+    /// 局を開始（山を作り直して配牌）
+    fn start_hand(&mut self) -> Vec<Event> {
+        self.phase = Phase::Dealing;
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.reset_for_new_hand(i == self.dealer);
+        }
+        self.current_player = self.dealer;
+        self.last_discard = None;
+        self.last_draw = None;
+        self.hand_result = None;
+        self.claim_window = None;
+        self.call_made = false;
+        self.rinshan_draw = false;
+        self.pending_kan_dora = 0;
+        self.kuikae_tiles.clear();
+        self.pending_riichi = None;
+
+        let mut events = vec![Event::HandStarted {
+            round_wind: self.round_wind,
+            round: self.round,
+            honba: self.honba,
+            dealer: self.dealer,
+        }];
+        self.initialize_wall();
+        events.extend(self.deal_initial_tiles());
+        events.extend(self.wall.dora_indicators().iter().map(|&indicator| Event::DoraRevealed { indicator }));
+        if let Some(tile) = self.last_draw {
+            events.push(Event::Drew { player: self.dealer, tile });
+        }
+        self.phase = Phase::AwaitingDiscard; // 親は14枚で打牌から
+
+        self.record(&events);
+        events
+    }
+
+    /// 牌譜に追記
+    fn record(&mut self, events: &[Event]) {
+        for event in events {
+            self.log.push(event.clone());
+        }
+    }
+
+    /// これまでの牌譜
+    pub fn log(&self) -> &GameLog {
+        &self.log
+    }
+
+    /// 次の局へ進む（親の連荘・輪番、本場の加算）
+    pub fn next_hand(&mut self) -> Result<Vec<Event>, RuleError> {
+        if self.phase != Phase::HandOver {
+            return Err(RuleError::WrongPhase(self.phase));
+        }
+
+        let is_draw = matches!(self.hand_result, Some(HandResult::Draw(_)));
+        if self.dealer_keeps_seat() {
+            self.honba += 1;
+        } else {
+            // 流局なら親が流れても積み棒は増える
+            self.honba = if is_draw { self.honba + 1 } else { 0 };
+            self.dealer = (self.dealer + 1) % self.player_count();
+            self.round += 1;
+            if self.round as usize > self.player_count() {
+                self.round = 1;
+                self.round_wind = next_wind(self.round_wind);
+            }
+        }
+
+        Ok(self.start_hand())
+    }
+
+    /// 終わった局で親が連荘するか
+    fn dealer_keeps_seat(&self) -> bool {
+        match &self.hand_result {
+            Some(HandResult::Win(wins)) => wins.iter().any(|win| win.winner == self.dealer),
+            Some(HandResult::Draw(draw)) => draw.tenpai.contains(&self.dealer), // テンパイ連荘
+            Some(HandResult::Abort(_)) => true,
+            None => false,
+        }
+    }
+
+    /// 終わった局で対局が終了するか
+    fn is_match_over(&self) -> bool {
+        // 飛び
+        if self.rules.tobi && self.players.iter().any(|p| p.score < 0) {
+            return true;
+        }
+        // 途中流局は局のやり直し
+        if matches!(self.hand_result, Some(HandResult::Abort(_))) {
+            return false;
+        }
+
+        let target = self.rules.target_score;
+        let reached = self.players.iter().any(|p| p.score >= target);
+        let winds = self.rules.length.wind_count();
+        let wind = wind_index(self.round_wind);
+        let dealer_keeps = self.dealer_keeps_seat();
+        let last_round = self.player_count() as u32;
+
+        // 延長戦はサドンデス（延長した場の最後まで）
+        if wind >= winds {
+            return reached || (self.round == last_round && !dealer_keeps);
+        }
+
+        let is_all_last = wind + 1 == winds && self.round == last_round;
+        if !is_all_last {
+            return false;
+        }
+
+        if dealer_keeps {
+            // オーラスの親がトップで返し点以上なら和了り止め
+            let dealer_score = self.players[self.dealer].score;
+            dealer_score >= target && self.players.iter().all(|p| p.score <= dealer_score)
+        } else {
+            reached || !self.rules.west_extension || winds == 4
+        }
+    }
+
     fn initialize_wall(&mut self) {
-        self.wall.clear();
+        let mut tiles = Vec::new();
         
-        // 数牌（各4枚）
-        for suit in [Suit::Man, Suit::Pin, Suit::Sou] {
+        // 数牌（各4枚、5はルールの枚数だけ赤ドラ。三人麻雀は萬子の2〜8を除く）
+        for (suit, &red_count) in [Suit::Man, Suit::Pin, Suit::Sou].into_iter().zip(&self.rules.red_fives) {
             for value in 1..=9 {
-                for _ in 0..4 {
-                    let is_red = value == 5 && self.wall.iter().filter(|t| 
-                        matches!(t.tile_type, TileType::Number { suit: s, value: 5 } if s == suit) && t.is_red
-                    ).count() == 0; // 各色5の1枚目のみ赤ドラ
-                    
-                    self.wall.push(Tile::new_number(suit, value, is_red));
+                if self.rules.is_sanma() && suit == Suit::Man && (2..=8).contains(&value) {
+                    continue;
+                }
+                for copy in 0..4 {
+                    let is_red = value == 5 && copy < red_count;
+                    tiles.push(Tile::new_number(suit, value, is_red));
                 }
             }
         }
@@ -64,415 +317,2589 @@ impl Game {
         // 字牌（各4枚）
         for honor in [Honor::Ton, Honor::Nan, Honor::Shaa, Honor::Pei, Honor::Haku, Honor::Hatsu, Honor::Chun] {
             for _ in 0..4 {
-                self.wall.push(Tile::new_honor(honor));
+                tiles.push(Tile::new_honor(honor));
             }
         }
 
-        // シャッフル
-        self.wall.shuffle(&mut thread_rng());
+        // シャッフルしてサイコロの目の位置から開門
+        tiles.shuffle(&mut self.rng);
+        self.dice = roll_dice(&mut self.rng);
+        let break_at = (self.dice[0] + self.dice[1]) as usize * 2;
+        tiles.rotate_left(break_at);
+        self.wall = if self.rules.is_sanma() {
+            Wall::with_rinshan(tiles, SANMA_RINSHAN_COUNT)
+        } else {
+            Wall::new(tiles)
+        };
         
         // ドラ表示牌を設定
-        if let Some(dora_indicator) = self.wall.pop() {
-            self.dora_indicators.push(dora_indicator);
-        }
+        self.wall.reveal_dora();
     }
 
-    fn deal_initial_tiles(&mut self) {
+    /// 配牌（親の14枚目はツモ扱い）
+    fn deal_initial_tiles(&mut self) -> Vec<Event> {
         // 親は14枚、子は13枚配る
         for _round in 0..3 {
-            for player_idx in 0..4 {
+            for player_idx in 0..self.player_count() {
                 for _ in 0..4 {
-                    if let Some(tile) = self.wall.pop() {
-                        self.players[player_idx].draw_tile(tile);
+                    if let Some(tile) = self.wall.draw() {
+                        self.players[player_idx].hand.add_tile(tile);
                     }
                 }
             }
         }
         
         // 最後の1枚ずつ
-        for player_idx in 0..4 {
-            if let Some(tile) = self.wall.pop() {
-                self.players[player_idx].draw_tile(tile);
+        for player_idx in 0..self.player_count() {
+            if let Some(tile) = self.wall.draw() {
+                self.players[player_idx].hand.add_tile(tile);
             }
         }
         
+        let events = self
+            .players
+            .iter()
+            .map(|player| Event::Dealt { player: player.id, tiles: player.hand.get_tiles().to_vec() })
+            .collect();
+
         // 親に追加の1枚
-        if let Some(tile) = self.wall.pop() {
+        if let Some(tile) = self.wall.draw() {
             self.players[self.dealer].draw_tile(tile);
+            self.last_draw = Some(tile);
         }
+        events
     }
 
-    pub fn draw_tile(&mut self) -> Option<Tile> {
-        self.wall.pop()
-    }
-
-    pub fn current_player_draw(&mut self) -> bool {
-        if let Some(tile) = self.draw_tile() {
-            self.players[self.current_player].draw_tile(tile);
-            true
-        } else {
-            false
+    /// 操作を検証して実行し、起きた出来事を返す（牌譜にも記録する）
+    pub fn apply(&mut self, action: Action) -> Result<Vec<Event>, RuleError> {
+        let mut events = self.apply_action(action)?;
+        if let Some(Event::HandEnded(result)) = events.last() {
+            let deltas = result.score_deltas(self.player_count());
+            events.push(Event::ScoresChanged { deltas });
         }
+        self.record(&events);
+        Ok(events)
     }
 
-    pub fn discard_tile(&mut self, tile: Tile) -> bool {
-        if self.players[self.current_player].discard_tile(tile) {
-            self.last_discard = Some(tile);
-            self.next_player();
-            true
-        } else {
-            false
+    fn apply_action(&mut self, action: Action) -> Result<Vec<Event>, RuleError> {
+        match action {
+            Action::Draw { player } => self.apply_draw(player),
+            Action::Discard { player, tile } => self.apply_discard(player, tile),
+            Action::Riichi { player } => self.apply_riichi(player),
+            Action::Tsumo { player } => self.apply_tsumo(player),
+            Action::Ankan { player, tile } => self.apply_ankan(player, tile),
+            Action::Kakan { player, tile } => self.apply_kakan(player, tile),
+            Action::KyuushuKyuuhai { player } => self.apply_kyuushu_kyuuhai(player),
+            Action::Kita { player } => self.apply_kita(player),
+            Action::Claim { player, claim } => self.apply_claim(player, claim),
         }
     }
 
-    pub fn next_player(&mut self) {
-        self.current_player = (self.current_player + 1) % 4;
+    /// フェーズと手番の確認
+    fn expect_turn(&self, phase: Phase, player: usize) -> Result<(), RuleError> {
+        if self.phase != phase {
+            return Err(RuleError::WrongPhase(self.phase));
+        }
+        if player != self.current_player {
+            return Err(RuleError::NotYourTurn);
+        }
+        Ok(())
     }
 
-    pub fn get_current_player(&self) -> &Player {
-        &self.players[self.current_player]
-    }
+    fn apply_draw(&mut self, player: usize) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDraw, player)?;
 
-    pub fn get_current_player_mut(&mut self) -> &mut Player {
-        &mut self.players[self.current_player]
+        let tile = self.wall.draw().ok_or(RuleError::WallEmpty)?;
+        self.players[player].draw_tile(tile);
+        self.last_draw = Some(tile);
+        self.phase = Phase::AwaitingDiscard;
+        Ok(vec![Event::Drew { player, tile }])
     }
 
-    pub fn can_someone_win(&self, tile: &Tile) -> Vec<usize> {
-        let mut winners = Vec::new();
-        for (i, player) in self.players.iter().enumerate() {
-            if i != self.current_player && player.can_win(tile) {
-                winners.push(i);
-            }
+    fn apply_discard(&mut self, player: usize, tile: Tile) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+        if !self.players[player].hand.get_tiles().contains(&tile) {
+            return Err(RuleError::TileNotInHand);
         }
-        winners
+        if self.is_kuikae(&tile) {
+            return Err(RuleError::Kuikae);
+        }
+        if !self.players[player].can_discard_after_riichi(&tile) {
+            return Err(RuleError::RiichiLocked);
+        }
+        self.kuikae_tiles.clear();
+
+        self.players[player].discard_tile(tile);
+        self.last_discard = Some(tile);
+        self.last_draw = None;
+        self.rinshan_draw = false;
+        self.next_player();
+
+        let (tsumogiri, riichi) = match self.players[player].discards.last() {
+            Some(discard) => (discard.tsumogiri, discard.riichi),
+            None => (false, false),
+        };
+        let mut events = vec![Event::Discarded { player, tile, tsumogiri, riichi }];
+        events.extend(self.reveal_pending_kan_dora());
+        events.extend(self.open_claim_window(tile));
+        Ok(events)
     }
 
-    /// チー可能かチェック（下家のみ）
-    pub fn can_chi(&self, player_idx: usize) -> bool {
-        if self.last_discard.is_none() {
-            return false;
+    fn apply_riichi(&mut self, player: usize) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+        if !self.can_riichi(player) {
+            return Err(RuleError::CannotRiichi);
         }
 
-        // チーは下家（前のプレイヤー）のみ可能
-        let prev_player = (self.current_player + 3) % 4;
-        if player_idx != prev_player {
-            return false;
+        let is_first_turn = self.players[player].discards.is_empty() && !self.call_made;
+        let turn = self.players[player].discards.len();
+        if !self.players[player].declare_riichi(turn) {
+            return Err(RuleError::CannotRiichi);
         }
+        self.players[player].double_riichi = is_first_turn;
+        self.pending_riichi = Some(player);
+        Ok(vec![Event::RiichiDeclared { player }])
+    }
 
-        let tile = self.last_discard.unwrap();
+    /// リーチできるか（ツモ山が人数分以上残っている）
+    pub fn can_riichi(&self, player_idx: usize) -> bool {
+        self.phase == Phase::AwaitingDiscard
+            && player_idx == self.current_player
+            && self.wall.remaining() >= self.player_count()
+            && self.players[player_idx].can_riichi()
+    }
 
-        // 数牌のみチー可能
-        if let TileType::Number { suit, value } = tile.tile_type {
-            let hand = &self.players[player_idx].hand;
-            let tiles = hand.get_tiles();
-
-            // パターン1: n-2, n-1, n（鳴き牌がn）
-            if value >= 3 {
-                let t1 = Tile::new_number(suit, value - 2, false);
-                let t2 = Tile::new_number(suit, value - 1, false);
-                if tiles.contains(&t1) && tiles.contains(&t2) {
-                    return true;
-                }
-            }
+    /// 宣言牌が通ったらリーチ成立（供託1000点を支払う）
+    fn accept_riichi(&mut self) -> Option<Event> {
+        let player = self.pending_riichi.take()?;
+        self.players[player].subtract_score(1000);
+        self.riichi_sticks += 1;
 
-            // パターン2: n-1, n, n+1（鳴き牌がn）
-            if value >= 2 && value <= 8 {
-                let t1 = Tile::new_number(suit, value - 1, false);
-                let t2 = Tile::new_number(suit, value + 1, false);
-                if tiles.contains(&t1) && tiles.contains(&t2) {
-                    return true;
-                }
-            }
+        let mut deltas = vec![0; self.player_count()];
+        deltas[player] = -1000;
+        Some(Event::ScoresChanged { deltas })
+    }
 
-            // パターン3: n, n+1, n+2（鳴き牌がn）
-            if value <= 7 {
-                let t1 = Tile::new_number(suit, value + 1, false);
-                let t2 = Tile::new_number(suit, value + 2, false);
-                if tiles.contains(&t1) && tiles.contains(&t2) {
-                    return true;
-                }
-            }
+    fn apply_tsumo(&mut self, player: usize) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+
+        let (winning_tile, scoring) = self.score_tsumo(player).ok_or(RuleError::CannotWin)?;
+        let payment = self.settle_win(player, None, &scoring);
+        let result = HandResult::Win(vec![WinResult {
+            winner: player,
+            loser: None,
+            winning_tile,
+            scoring,
+            payment,
+        }]);
+        Ok(vec![self.finish_hand(result)])
+    }
+
+    fn apply_ankan(&mut self, player: usize, tile: Tile) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+        if !self.can_ankan(player).contains(&tile.normalized()) {
+            return Err(RuleError::CannotKan);
         }
 
-        false
+        self.execute_ankan(player, tile);
+        let mut events = vec![Event::Ankan { player, tile }];
+        events.extend(self.open_chankan_window(player, tile, KanType::Closed));
+        Ok(events)
     }
 
-    /// ポン可能かチェック
-    pub fn can_pon(&self, player_idx: usize) -> bool {
-        if self.last_discard.is_none() || player_idx == self.current_player {
-            return false;
+    fn apply_kakan(&mut self, player: usize, tile: Tile) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+        if !self.can_kakan(player).contains(&tile) {
+            return Err(RuleError::CannotKan);
         }
 
-        let tile = self.last_discard.unwrap();
-        let hand = &self.players[player_idx].hand;
-        let tiles = hand.get_tiles();
-
-        // 同じ牌が2枚以上あればポン可能
-        tiles.iter().filter(|&&t| t == tile).count() >= 2
+        self.players[player].hand.upgrade_pon(&tile);
+        let mut events = vec![Event::Kakan { player, tile }];
+        events.extend(self.open_chankan_window(player, tile, KanType::Added));
+        Ok(events)
     }
 
-    /// カン可能かチェック（明槓）
-    pub fn can_kan(&self, player_idx: usize) -> bool {
-        if self.last_discard.is_none() || player_idx == self.current_player {
-            return false;
+    /// カンした牌に対する槍槓の受付（ロンできる人がいなければそのままカンを成立させる）
+    fn open_chankan_window(&mut self, player: usize, tile: Tile, kan_type: KanType) -> Vec<Event> {
+        self.phase = Phase::ClaimWindow;
+        self.claim_window = Some(ClaimWindow {
+            discarder: player,
+            tile,
+            pending: Vec::new(),
+            claims: Vec::new(),
+            robbed_kan: Some(kan_type),
+        });
+
+        let pending: Vec<usize> = self.others(player).into_iter().filter(|&i| self.can_ron(i)).collect();
+        if pending.is_empty() {
+            self.claim_window = None;
+            return self.complete_kan(player, kan_type);
         }
 
-        let tile = self.last_discard.unwrap();
-        let hand = &self.players[player_idx].hand;
-        let tiles = hand.get_tiles();
-
-        // 同じ牌が3枚あれば明槓可能
-        tiles.iter().filter(|&&t| t == tile).count() >= 3
+        if let Some(window) = self.claim_window.as_mut() {
+            window.pending = pending.clone();
+        }
+        vec![Event::ClaimWindowOpened { discarder: player, tile, pending }]
     }
 
-    /// 暗槓可能な牌のリストを取得
-    pub fn can_ankan(&self, player_idx: usize) -> Vec<Tile> {
-        let hand = &self.players[player_idx].hand;
-        let tiles = hand.get_tiles();
-        let mut ankan_tiles = Vec::new();
+    /// 槍槓されなかったカンを成立させる
+    fn complete_kan(&mut self, player: usize, kan_type: KanType) -> Vec<Event> {
+        self.phase = Phase::AwaitingDiscard;
+        self.current_player = player;
+        self.on_call();
+        let timing = match kan_type {
+            KanType::Closed => self.rules.closed_kan_dora,
+            KanType::Open | KanType::Added => self.rules.open_kan_dora,
+        };
+        self.after_kan(player, timing)
+    }
 
-        use std::collections::HashMap;
-        let mut tile_counts = HashMap::new();
-        for tile in tiles {
-            *tile_counts.entry(*tile).or_insert(0) += 1;
+    fn apply_kyuushu_kyuuhai(&mut self, player: usize) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+        if !self.can_kyuushu_kyuuhai(player) {
+            return Err(RuleError::CannotAbort);
         }
+        Ok(vec![self.finish_hand(HandResult::Abort(AbortReason::KyuushuKyuuhai(player)))])
+    }
 
-        for (tile, count) in tile_counts {
-            if count >= 4 {
-                ankan_tiles.push(tile);
-            }
+    fn apply_kita(&mut self, player: usize) -> Result<Vec<Event>, RuleError> {
+        self.expect_turn(Phase::AwaitingDiscard, player)?;
+        if !self.can_kita(player) {
+            return Err(RuleError::CannotKita);
         }
 
-        ankan_tiles
-    }
+        self.players[player].hand.discard(&Tile::new_honor(Honor::Pei));
+        self.players[player].kita += 1;
 
-    /// チーを実行
-    pub fn do_chi(&mut self, player_idx: usize, pattern: usize) -> bool {
-        if !self.can_chi(player_idx) {
-            return false;
+        // 嶺上牌を補充（ドラはめくらない）
+        let mut events = vec![Event::Kita { player }];
+        if let Some(tile) = self.wall.draw_rinshan() {
+            self.players[player].draw_tile(tile);
+            self.last_draw = Some(tile);
+            self.rinshan_draw = true;
+            events.push(Event::Drew { player, tile });
         }
+        Ok(events)
+    }
 
-        let tile = self.last_discard.unwrap();
+    fn apply_claim(&mut self, player: usize, claim: Claim) -> Result<Vec<Event>, RuleError> {
+        if self.phase != Phase::ClaimWindow {
+            return Err(RuleError::WrongPhase(self.phase));
+        }
+        let is_pending = match &self.claim_window {
+            Some(window) => window.pending.contains(&player),
+            None => false,
+        };
+        if !is_pending {
+            return Err(RuleError::NotYourTurn);
+        }
+        if claim != Claim::Pass && !self.available_claims(player).contains(&claim) {
+            return Err(RuleError::InvalidClaim);
+        }
 
-        if let TileType::Number { suit, value } = tile.tile_type {
-            let (t1, t2) = match pattern {
-                0 => {
-                    // n-2, n-1, n
-                    if value < 3 {
-                        return false;
-                    }
-                    (
-                        Tile::new_number(suit, value - 2, false),
-                        Tile::new_number(suit, value - 1, false),
-                    )
-                }
-                1 => {
-                    // n-1, n, n+1
-                    if value < 2 || value > 8 {
-                        return false;
-                    }
-                    (
-                        Tile::new_number(suit, value - 1, false),
-                        Tile::new_number(suit, value + 1, false),
-                    )
-                }
-                2 => {
-                    // n, n+1, n+2
-                    if value > 7 {
-                        return false;
-                    }
-                    (
-                        Tile::new_number(suit, value + 1, false),
-                        Tile::new_number(suit, value + 2, false),
-                    )
-                }
-                _ => return false,
-            };
+        // ロンできるのに見逃した（まだ13枚のうちにフリテンにする）
+        if claim != Claim::Ron && self.available_claims(player).contains(&Claim::Ron) {
+            self.players[player].miss_win();
+        }
 
-            let player = &mut self.players[player_idx];
-            if !player.hand.remove_tile(&t1) || !player.hand.remove_tile(&t2) {
-                return false;
+        let mut all_responded = false;
+        if let Some(window) = self.claim_window.as_mut() {
+            window.pending.retain(|&i| i != player);
+            if claim != Claim::Pass {
+                window.claims.push((player, claim));
             }
+            all_responded = window.pending.is_empty();
+        }
 
-            let meld = crate::hand::Meld {
-                meld_type: crate::hand::MeldType::Chi,
-                tiles: vec![t1, tile, t2],
-                is_open: true,
-            };
-
-            player.hand.add_meld(meld);
-            self.last_discard = None;
-            self.current_player = player_idx;
-            true
+        if all_responded {
+            Ok(self.resolve_claims())
         } else {
-            false
+            Ok(Vec::new())
         }
     }
 
-    /// ポンを実行
-    pub fn do_pon(&mut self, player_idx: usize) -> bool {
-        if !self.can_pon(player_idx) {
-            return false;
+    /// カンの後の槓ドラと嶺上牌のツモ
+    fn after_kan(&mut self, player: usize, timing: KanDoraTiming) -> Vec<Event> {
+        // 連続したカンでは前のカンの槓ドラを先にめくる
+        let mut events = self.reveal_pending_kan_dora();
+        match timing {
+            KanDoraTiming::Immediate => events.extend(self.wall.reveal_dora().map(|indicator| Event::DoraRevealed { indicator })),
+            KanDoraTiming::AfterDiscard => self.pending_kan_dora += 1,
         }
 
-        let tile = self.last_discard.unwrap();
-        let player = &mut self.players[player_idx];
+        if let Some(tile) = self.wall.draw_rinshan() {
+            self.players[player].draw_tile(tile);
+            self.last_draw = Some(tile);
+            self.rinshan_draw = true;
+            events.push(Event::Drew { player, tile });
+        }
+        events
+    }
 
-        // 同じ牌を2枚削除
-        if !player.hand.remove_tile(&tile) || !player.hand.remove_tile(&tile) {
-            return false;
+    /// 打牌後にめくる槓ドラをめくる
+    fn reveal_pending_kan_dora(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        for _ in 0..std::mem::take(&mut self.pending_kan_dora) {
+            if let Some(indicator) = self.wall.reveal_dora() {
+                events.push(Event::DoraRevealed { indicator });
+            }
         }
+        events
+    }
 
-        let meld = crate::hand::Meld {
-            meld_type: crate::hand::MeldType::Pon,
-            tiles: vec![tile, tile, tile],
-            is_open: true,
+    /// 局を終了する
+    fn finish_hand(&mut self, result: HandResult) -> Event {
+        self.hand_result = Some(result.clone());
+        self.claim_window = None;
+        self.phase = if self.is_match_over() {
+            Phase::GameOver
+        } else {
+            Phase::HandOver
         };
+        Event::HandEnded(result)
+    }
 
-        player.hand.add_meld(meld);
-        self.last_discard = None;
-        self.current_player = player_idx;
-        true
+    pub fn current_player_draw(&mut self) -> bool {
+        self.apply(Action::Draw { player: self.current_player }).is_ok()
     }
 
-    /// 明槓を実行
-    pub fn do_kan(&mut self, player_idx: usize) -> bool {
-        if !self.can_kan(player_idx) {
-            return false;
-        }
+    pub fn discard_tile(&mut self, tile: Tile) -> bool {
+        self.apply(Action::Discard { player: self.current_player, tile }).is_ok()
+    }
 
-        let tile = self.last_discard.unwrap();
-        let player = &mut self.players[player_idx];
+    /// 直前の打牌で和了できたのにロンしなかったプレイヤーをフリテンにする
+    fn record_missed_wins(&mut self) {
+        let tile = match self.last_discard {
+            Some(tile) => tile,
+            None => return,
+        };
+        let discarder = self.discarder();
 
-        // 同じ牌を3枚削除
-        for _ in 0..3 {
-            if !player.hand.remove_tile(&tile) {
-                return false;
+        for (i, player) in self.players.iter_mut().enumerate() {
+            if i != discarder && player.can_win(&tile) {
+                player.miss_win();
             }
         }
+    }
 
-        let meld = crate::hand::Meld {
-            meld_type: crate::hand::MeldType::Kan,
-            tiles: vec![tile, tile, tile, tile],
-            is_open: true,
-        };
-
-        player.hand.add_meld(meld);
-        self.last_discard = None;
+    /// 打牌に対する鳴き・ロンの受付を開始（何もできないプレイヤーは自動でパス）
+    fn open_claim_window(&mut self, tile: Tile) -> Vec<Event> {
+        let discarder = self.discarder();
+        self.phase = Phase::ClaimWindow;
+        self.claim_window = Some(ClaimWindow {
+            discarder,
+            tile,
+            pending: Vec::new(),
+            claims: Vec::new(),
+            robbed_kan: None,
+        });
+
+        let pending: Vec<usize> = self
+            .others(discarder)
+            .into_iter()
+            .filter(|&i| !self.available_claims(i).is_empty())
+            .collect();
 
-        // 槓ドラ追加
-        if let Some(dora_indicator) = self.wall.pop() {
-            self.dora_indicators.push(dora_indicator);
+        if pending.is_empty() {
+            self.claim_window = None;
+            return self.pass_turn();
         }
 
-        // 嶺上牌をツモ
-        if let Some(rinshan_tile) = self.wall.pop() {
-            self.players[player_idx].draw_tile(rinshan_tile);
+        if let Some(window) = self.claim_window.as_mut() {
+            window.pending = pending.clone();
         }
-
-        self.current_player = player_idx;
-        true
+        vec![Event::ClaimWindowOpened { discarder, tile, pending }]
     }
 
-    /// 暗槓を実行
-    pub fn do_ankan(&mut self, player_idx: usize, tile: Tile) -> bool {
-        let player = &mut self.players[player_idx];
-
-        // 同じ牌を4枚削除
-        for _ in 0..4 {
-            if !player.hand.remove_tile(&tile) {
-                return false;
-            }
+    /// 九種九牌を宣言できるか（第一ツモで么九牌が9種類以上）
+    pub fn can_kyuushu_kyuuhai(&self, player_idx: usize) -> bool {
+        if !self.rules.abortive_draws.kyuushu_kyuuhai
+            || self.phase != Phase::AwaitingDiscard
+            || player_idx != self.current_player
+        {
+            return false;
+        }
+        let player = &self.players[player_idx];
+        if !player.discards.is_empty() || self.call_made {
+            return false;
         }
 
-        let meld = crate::hand::Meld {
-            meld_type: crate::hand::MeldType::Kan,
-            tiles: vec![tile, tile, tile, tile],
-            is_open: false,
-        };
-
-        player.hand.add_meld(meld);
+        let mut kinds: Vec<usize> = player
+            .hand
+            .get_tiles()
+            .iter()
+            .filter(|tile| matches!(tile.tile_type, TileType::Honor(_) | TileType::Number { value: 1 | 9, .. }))
+            .map(|tile| tile.index())
+            .collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        kinds.len() >= 9
+    }
 
-        // 槓ドラ追加
-        if let Some(dora_indicator) = self.wall.pop() {
-            self.dora_indicators.push(dora_indicator);
+    /// 北抜きできるか（三人麻雀でツモ後に北を持っている、リーチ後はツモった北のみ）
+    pub fn can_kita(&self, player_idx: usize) -> bool {
+        if !self.rules.is_sanma()
+            || self.phase != Phase::AwaitingDiscard
+            || player_idx != self.current_player
+            || self.last_draw.is_none()
+            || self.wall.is_empty()
+            || self.wall.rinshan_remaining() == 0
+        {
+            return false;
         }
 
-        // 嶺上牌をツモ
-        if let Some(rinshan_tile) = self.wall.pop() {
-            self.players[player_idx].draw_tile(rinshan_tile);
+        let north = Tile::new_honor(Honor::Pei);
+        let player = &self.players[player_idx];
+        if player.is_riichi {
+            player.hand.drawn_tile() == Some(north)
+        } else {
+            player.hand.get_tiles().contains(&north)
         }
+    }
 
-        true
+    /// 北を抜きドラにして嶺上牌をツモる
+    pub fn declare_kita(&mut self, player_idx: usize) -> bool {
+        self.apply(Action::Kita { player: player_idx }).is_ok()
     }
 
-    pub fn is_game_over(&self) -> bool {
-        self.wall.is_empty() || self.players.iter().any(|p| p.score <= 0)
+    /// 九種九牌で流局を宣言
+    pub fn declare_kyuushu_kyuuhai(&mut self, player_idx: usize) -> bool {
+        self.apply(Action::KyuushuKyuuhai { player: player_idx }).is_ok()
     }
 
-    pub fn get_wall_count(&self) -> usize {
-        self.wall.len()
+    /// 受付中の打牌に対して選択できる応答（パス以外）
+    pub fn available_claims(&self, player_idx: usize) -> Vec<Claim> {
+        let mut claims = Vec::new();
+        if self.can_ron(player_idx) {
+            claims.push(Claim::Ron);
+        }
+        if self.can_kan(player_idx) {
+            claims.push(Claim::Kan);
+        }
+        if self.can_pon(player_idx) {
+            claims.push(Claim::Pon);
+        }
+        claims.extend(self.chi_patterns(player_idx).into_iter().map(Claim::Chi));
+        claims
     }
 
-    pub fn get_dora_indicators(&self) -> &Vec<Tile> {
-        &self.dora_indicators
+    /// 打牌に対する応答を登録（全員が応答したら優先順位に従って処理）
+    pub fn respond(&mut self, player_idx: usize, claim: Claim) -> bool {
+        self.apply(Action::Claim { player: player_idx, claim }).is_ok()
     }
 
-    pub fn get_game_state_string(&self) -> String {
-        let mut result = String::new();
-        
-        result.push_str(&format!("Round: {} | Wall: {} tiles\n", self.round, self.wall.len()));
-        result.push_str(&format!("Dora indicators: {}\n", 
-            self.dora_indicators.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" ")));
-        
-        for (i, player) in self.players.iter().enumerate() {
-            let marker = if i == self.current_player { ">" } else { " " };
-            let dealer_mark = if player.is_dealer { "親" } else { " " };
-            result.push_str(&format!("{}{} {} ({}点): {}\n", 
-                marker, dealer_mark, player.name, player.score, player.get_hand_string()));
-            
-            if !player.discards.is_empty() {
-                result.push_str(&format!("  河: {}\n", player.get_discards_string()));
-            }
+    /// 応答を優先順位（ロン > ポン・カン > チー）に従って処理
+    fn resolve_claims(&mut self) -> Vec<Event> {
+        let (claims, tile, discarder, robbed_kan) = match &self.claim_window {
+            Some(window) => (window.claims.clone(), window.tile, window.discarder, window.robbed_kan),
+            None => return Vec::new(),
+        };
+
+        // ロンは全員分（頭ハネ・席順は settle_ron で処理）
+        let ron_players: Vec<usize> = claims
+            .iter()
+            .filter(|(_, claim)| *claim == Claim::Ron)
+            .map(|(i, _)| *i)
+            .collect();
+        if ron_players.len() == 3 && self.rules.abortive_draws.sanchahou {
+            return vec![self.finish_hand(HandResult::Abort(AbortReason::Sanchahou))];
         }
-        
-        if let Some(tile) = self.last_discard {
-            result.push_str(&format!("Last discard: {}\n", tile.to_string()));
+        if !ron_players.is_empty() {
+            return self.settle_ron(&ron_players).into_iter().collect();
         }
-        
-        result
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // 槍槓を見逃したプレイヤーは同巡内フリテン
+        if let Some(kan_type) = robbed_kan {
+            let missed: Vec<usize> = (0..self.player_count()).filter(|&i| self.can_ron(i)).collect();
+            for i in missed {
+                self.players[i].miss_win();
+            }
+            self.claim_window = None;
+            return self.complete_kan(discarder, kan_type);
+        }
 
-    #[test]
+        let call = claims
+            .iter()
+            .find(|(_, claim)| matches!(claim, Claim::Pon | Claim::Kan))
+            .or_else(|| claims.iter().find(|(_, claim)| matches!(claim, Claim::Chi(_))))
+            .copied();
+        let called = match call {
+            Some((player_idx, Claim::Kan)) => self.execute_kan(player_idx),
+            Some((player_idx, Claim::Pon)) => self.execute_pon(player_idx),
+            Some((player_idx, Claim::Chi(pattern))) => self.execute_chi(player_idx, pattern),
+            _ => false,
+        };
+
+        match call {
+            Some((player, claim)) if called => {
+                self.phase = Phase::AwaitingDiscard;
+                let mut events: Vec<Event> = self.accept_riichi().into_iter().collect();
+                events.push(Event::Called { player, claim, tile });
+                if claim == Claim::Kan {
+                    events.extend(self.after_kan(player, self.rules.open_kan_dora));
+                }
+                events
+            }
+            _ => {
+                // 全員パス
+                self.claim_window = None;
+                self.pass_turn()
+            }
+        }
+    }
+
+    /// 打牌が通ったら次のツモへ（リーチの成立、途中流局・荒牌流局の判定）
+    fn pass_turn(&mut self) -> Vec<Event> {
+        // 和了形なのにロンしなかったプレイヤーは、次のツモの前にフリテンにする
+        self.record_missed_wins();
+
+        let mut events: Vec<Event> = self.accept_riichi().into_iter().collect();
+        if let Some(reason) = self.abortive_draw() {
+            events.push(self.finish_hand(HandResult::Abort(reason)));
+        } else if self.wall.is_empty() {
+            events.push(self.exhaustive_draw());
+        } else {
+            self.phase = Phase::AwaitingDraw;
+        }
+        events
+    }
+
+    /// 打牌が通った時点で成立する途中流局
+    fn abortive_draw(&self) -> Option<AbortReason> {
+        let rules = self.rules.abortive_draws;
+
+        // 四風連打・四家立直は4人麻雀のみ
+        let is_yonma = !self.rules.is_sanma();
+
+        // 1巡目に全員が同じ風牌を捨てた
+        if is_yonma && rules.suufon_renda && !self.call_made && self.players.iter().all(|p| p.discards.len() == 1) {
+            let first = self.players[0].discards[0].tile;
+            if matches!(first.tile_type, TileType::Honor(Honor::Ton | Honor::Nan | Honor::Shaa | Honor::Pei))
+                && self.players.iter().all(|p| p.discards[0].tile == first)
+            {
+                return Some(AbortReason::SuufonRenda);
+            }
+        }
+
+        // 4人目のリーチ宣言牌が通った
+        if is_yonma && rules.suucha_riichi && self.players.iter().all(|p| p.is_riichi) {
+            return Some(AbortReason::SuuchaRiichi);
+        }
+
+        // 4つ目のカンの後の打牌が通った（1人で4回なら四槓子の可能性があるので続行）
+        if rules.suukaikan {
+            let kans: Vec<usize> = self
+                .players
+                .iter()
+                .map(|p| p.hand.get_melds().iter().filter(|m| matches!(m.meld_type, MeldType::Kan(_))).count())
+                .collect();
+            if kans.iter().sum::<usize>() >= 4 && !kans.contains(&4) {
+                return Some(AbortReason::Suukaikan);
+            }
+        }
+
+        None
+    }
+
+    /// 荒牌流局の精算（流し満貫かノーテン罰符、4人なら3000点・3人なら2000点）
+    fn exhaustive_draw(&mut self) -> Event {
+        let count = self.player_count();
+        let tenpai: Vec<usize> = (0..count).filter(|&i| self.players[i].is_tenpai()).collect();
+        let nagashi: Vec<usize> = if self.rules.nagashi_mangan {
+            (0..count).filter(|&i| self.players[i].is_nagashi()).collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut transfers = Vec::new();
+        if !nagashi.is_empty() {
+            // 満貫ツモ扱い（積み棒・供託は動かない）
+            for &winner in &nagashi {
+                let payment = self.payment(2000, winner, None, 0, 0);
+                transfers.extend(payment.transfers);
+            }
+        } else if !tenpai.is_empty() && tenpai.len() < count {
+            let noten: Vec<usize> = (0..count).filter(|i| !tenpai.contains(i)).collect();
+            let points = 1000 * (count as u32 - 1) / (tenpai.len() * noten.len()) as u32;
+            for &from in &noten {
+                for &to in &tenpai {
+                    transfers.push(Transfer { from, to, points });
+                }
+            }
+        }
+
+        let result = DrawResult { tenpai, nagashi, transfers };
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.add_score(result.delta(i));
+        }
+        self.finish_hand(HandResult::Draw(result))
+    }
+
+    fn next_player(&mut self) {
+        self.current_player = (self.current_player + 1) % self.player_count();
+    }
+
+    /// 対局人数
+    pub fn player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    /// 指定プレイヤー（状態の変更は apply / respond を通す）
+    pub fn player(&self, player_idx: usize) -> &Player {
+        &self.players[player_idx]
+    }
+
+    /// 全プレイヤー（席順）
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    /// 手番のプレイヤー
+    pub fn current_player(&self) -> usize {
+        self.current_player
+    }
+
+    /// 対局の進行状態
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// 打牌に対する鳴き・ロンの受付（受付中でなければ None）
+    pub fn claim_window(&self) -> Option<&ClaimWindow> {
+        self.claim_window.as_ref()
+    }
+
+    /// 直前の局の結果
+    pub fn hand_result(&self) -> Option<&HandResult> {
+        self.hand_result.as_ref()
+    }
+
+    /// 対局のルール
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// 山・サイコロを決めるシード値
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// 親の席
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+
+    /// 積み棒の本数
+    pub fn honba(&self) -> u32 {
+        self.honba
+    }
+
+    /// 供託リーチ棒の本数
+    pub fn riichi_sticks(&self) -> u32 {
+        self.riichi_sticks
+    }
+
+    /// 指定プレイヤー以外を下家から順に
+    fn others(&self, player: usize) -> Vec<usize> {
+        let count = self.player_count();
+        (1..count).map(|offset| (player + offset) % count).collect()
+    }
+
+    pub fn get_current_player(&self) -> &Player {
+        &self.players[self.current_player]
+    }
+
+    pub fn can_someone_win(&self, tile: &Tile) -> Vec<usize> {
+        let mut winners = Vec::new();
+        for (i, player) in self.players.iter().enumerate() {
+            if i != self.current_player && player.can_win(tile) {
+                winners.push(i);
+            }
+        }
+        winners
+    }
+
+    /// 鳴きの対象となる打牌（受付中で、打牌者・リーチ者以外、河底牌は鳴けない）
+    fn callable_tile(&self, player_idx: usize) -> Option<Tile> {
+        let window = self.claim_window.as_ref()?;
+        if window.robbed_kan.is_some()
+            || player_idx == window.discarder
+            || self.players[player_idx].is_riichi
+            || self.wall.is_empty()
+        {
+            return None;
+        }
+        Some(window.tile)
+    }
+
+    /// チー可能なパターンの一覧（0: n-2,n-1,n / 1: n-1,n,n+1 / 2: n,n+1,n+2）
+    pub fn chi_patterns(&self, player_idx: usize) -> Vec<usize> {
+        let tile = match self.callable_tile(player_idx) {
+            Some(tile) => tile,
+            None => return Vec::new(),
+        };
+
+        // チーは打牌者の下家のみ可能（三人麻雀はチーなし）
+        if self.rules.is_sanma() || player_idx != (self.discarder() + 1) % self.player_count() {
+            return Vec::new();
+        }
+
+        // 喰い替えしかできなくなる形では鳴けない
+        (0..3)
+            .filter(|&pattern| match self.chi_tiles(player_idx, tile, pattern) {
+                Some(used) => self.leaves_legal_discard(player_idx, &used, &kuikae_tiles(tile, Some(pattern))),
+                None => false,
+            })
+            .collect()
+    }
+
+    /// チーに使う手牌の2枚（赤ドラも同じ牌として扱う、数牌のみ）
+    fn chi_tiles(&self, player_idx: usize, tile: Tile, pattern: usize) -> Option<Vec<Tile>> {
+        let (suit, value) = match tile.tile_type {
+            TileType::Number { suit, value } => (suit, value as i8),
+            TileType::Honor(_) => return None,
+        };
+        let (a, b) = [(-2, -1), (-1, 1), (1, 2)].get(pattern).copied()?;
+
+        let hand = &self.players[player_idx].hand;
+        let mut used = Vec::new();
+        for offset in [a, b] {
+            let v = value + offset;
+            if !(1..=9).contains(&v) {
+                return None;
+            }
+            used.extend(hand.tiles_of_kind(&Tile::new_number(suit, v as u8, false), 1)?);
+        }
+        Some(used)
+    }
+
+    /// 鳴いた後に喰い替えにならない打牌が残るか
+    fn leaves_legal_discard(&self, player_idx: usize, used: &[Tile], forbidden: &[Tile]) -> bool {
+        if self.rules.allow_kuikae {
+            return true;
+        }
+        let mut hand = self.players[player_idx].hand.clone();
+        for tile in used {
+            hand.remove_tile(tile);
+        }
+        hand.get_tiles().iter().any(|tile| !forbidden.contains(&tile.normalized()))
+    }
+
+    /// 喰い替えで打牌できない牌か
+    fn is_kuikae(&self, tile: &Tile) -> bool {
+        self.kuikae_tiles.contains(&tile.normalized())
+    }
+
+    /// 打牌できる牌の一覧（喰い替えの牌・リーチ後のツモ牌以外を除く）
+    pub fn legal_discards(&self, player_idx: usize) -> Vec<Tile> {
+        if self.phase != Phase::AwaitingDiscard || player_idx != self.current_player {
+            return Vec::new();
+        }
+        let player = &self.players[player_idx];
+        let mut tiles: Vec<Tile> = player
+            .hand
+            .get_tiles()
+            .iter()
+            .filter(|tile| !self.is_kuikae(tile) && player.can_discard_after_riichi(tile))
+            .copied()
+            .collect();
+        tiles.sort_by_key(|tile| (tile.index(), tile.is_red));
+        tiles.dedup();
+        tiles
+    }
+
+    /// チー可能かチェック（下家のみ）
+    pub fn can_chi(&self, player_idx: usize) -> bool {
+        !self.chi_patterns(player_idx).is_empty()
+    }
+
+    /// ポン可能かチェック
+    pub fn can_pon(&self, player_idx: usize) -> bool {
+        let tile = match self.callable_tile(player_idx) {
+            Some(tile) => tile,
+            None => return false,
+        };
+
+        // 同じ牌（赤ドラを含む）が2枚以上あればポン可能
+        match self.players[player_idx].hand.tiles_of_kind(&tile, 2) {
+            Some(used) => self.leaves_legal_discard(player_idx, &used, &kuikae_tiles(tile, None)),
+            None => false,
+        }
+    }
+
+    /// カン可能かチェック（明槓）
+    pub fn can_kan(&self, player_idx: usize) -> bool {
+        let tile = match self.callable_tile(player_idx) {
+            Some(tile) => tile,
+            None => return false,
+        };
+
+        // 嶺上牌が残っていないか、すでに4回カンされていればカンできない
+        if self.wall.rinshan_remaining() == 0 || self.kan_limit_reached() {
+            return false;
+        }
+
+        // 同じ牌（赤ドラを含む）が3枚あれば明槓可能
+        self.players[player_idx].hand.tiles_of_kind(&tile, 3).is_some()
+    }
+
+    /// 卓全体でカンが4回行われたか（三人麻雀は嶺上牌が8枚あるので牌数では判定できない）
+    fn kan_limit_reached(&self) -> bool {
+        let kans: usize = self
+            .players
+            .iter()
+            .map(|p| p.hand.get_melds().iter().filter(|m| matches!(m.meld_type, MeldType::Kan(_))).count())
+            .sum();
+        kans >= 4
+    }
+
+    /// 暗槓可能な牌のリストを取得
+    ///
+    /// 赤ドラを含む4枚も同じ牌として数え、赤ドラ情報を落とした牌で返す
+    pub fn can_ankan(&self, player_idx: usize) -> Vec<Tile> {
+        // 海底牌ではカンできず、嶺上牌が尽きているか4回カンされていてもできない
+        if self.phase != Phase::AwaitingDiscard
+            || player_idx != self.current_player
+            || self.wall.is_empty()
+            || self.wall.rinshan_remaining() == 0
+            || self.kan_limit_reached()
+        {
+            return Vec::new();
+        }
+
+        let hand = &self.players[player_idx].hand;
+        let tiles = hand.get_tiles();
+        let mut ankan_tiles = Vec::new();
+
+        use std::collections::HashMap;
+        let mut tile_counts = HashMap::new();
+        for tile in tiles {
+            *tile_counts.entry(tile.normalized()).or_insert(0) += 1;
+        }
+
+        for (tile, count) in tile_counts {
+            if count >= 4 {
+                ankan_tiles.push(tile);
+            }
+        }
+        ankan_tiles.sort_by_key(|tile| tile.index());
+
+        // リーチ後はツモ牌で、待ちが変わらない暗槓のみ
+        if self.players[player_idx].is_riichi {
+            ankan_tiles.retain(|tile| match hand.drawn_tile() {
+                Some(drawn) => drawn.normalized() == *tile && keeps_waits_after_ankan(hand, drawn),
+                None => false,
+            });
+        }
+
+        ankan_tiles
+    }
+
+    /// 加槓可能な牌のリストを取得（ポンした面子と同じ牌を持っている）
+    pub fn can_kakan(&self, player_idx: usize) -> Vec<Tile> {
+        if self.phase != Phase::AwaitingDiscard
+            || player_idx != self.current_player
+            || self.wall.is_empty()
+            || self.wall.rinshan_remaining() == 0
+            || self.kan_limit_reached()
+        {
+            return Vec::new();
+        }
+
+        let hand = &self.players[player_idx].hand;
+        let mut kakan_tiles: Vec<Tile> = hand
+            .get_tiles()
+            .iter()
+            .filter(|tile| {
+                hand.get_melds()
+                    .iter()
+                    .any(|meld| meld.meld_type == MeldType::Pon && meld.tiles[0].normalized() == tile.normalized())
+            })
+            .copied()
+            .collect();
+        kakan_tiles.dedup();
+        kakan_tiles
+    }
+
+    /// 加槓を実行
+    pub fn do_kakan(&mut self, player_idx: usize, tile: Tile) -> bool {
+        self.apply(Action::Kakan { player: player_idx, tile }).is_ok()
+    }
+
+    /// チーを宣言
+    pub fn do_chi(&mut self, player_idx: usize, pattern: usize) -> bool {
+        self.respond(player_idx, Claim::Chi(pattern))
+    }
+
+    /// ポンを宣言
+    pub fn do_pon(&mut self, player_idx: usize) -> bool {
+        self.respond(player_idx, Claim::Pon)
+    }
+
+    /// 明槓を宣言
+    pub fn do_kan(&mut self, player_idx: usize) -> bool {
+        self.respond(player_idx, Claim::Kan)
+    }
+
+    /// 暗槓を宣言
+    pub fn do_ankan(&mut self, player_idx: usize, tile: Tile) -> bool {
+        self.apply(Action::Ankan { player: player_idx, tile }).is_ok()
+    }
+
+    /// チーを実行
+    fn execute_chi(&mut self, player_idx: usize, pattern: usize) -> bool {
+        if !self.chi_patterns(player_idx).contains(&pattern) {
+            return false;
+        }
+
+        let tile = self.last_discard.unwrap();
+        let used = match self.chi_tiles(player_idx, tile, pattern) {
+            Some(used) => used,
+            None => return false,
+        };
+
+        let from = self.discarder();
+        let player = &mut self.players[player_idx];
+
+        // 鳴いた牌を手牌に加えてから、手牌の実際の牌と合わせて面子として抜き出す
+        player.hand.add_tile(tile);
+        let meld = crate::hand::Meld {
+            meld_type: crate::hand::MeldType::Chi,
+            tiles: vec![used[0], tile, used[1]],
+            is_open: true,
+            from: Some(from),
+            called_tile: Some(tile),
+        };
+
+        player.hand.add_meld(meld);
+        self.finish_call(player_idx);
+        self.current_player = player_idx;
+        self.on_call();
+        if !self.rules.allow_kuikae {
+            self.kuikae_tiles = kuikae_tiles(tile, Some(pattern));
+        }
+        true
+    }
+
+    /// ポンを実行
+    fn execute_pon(&mut self, player_idx: usize) -> bool {
+        if !self.can_pon(player_idx) {
+            return false;
+        }
+
+        let tile = self.last_discard.unwrap();
+        let from = self.discarder();
+        let player = &mut self.players[player_idx];
+        let mut tiles = match player.hand.tiles_of_kind(&tile, 2) {
+            Some(tiles) => tiles,
+            None => return false,
+        };
+        tiles.push(tile);
+
+        // 鳴いた牌を手牌に加えてから、手牌の実際の牌と合わせて面子として抜き出す
+        player.hand.add_tile(tile);
+        let meld = crate::hand::Meld {
+            meld_type: crate::hand::MeldType::Pon,
+            tiles,
+            is_open: true,
+            from: Some(from),
+            called_tile: Some(tile),
+        };
+
+        player.hand.add_meld(meld);
+        self.finish_call(player_idx);
+        self.current_player = player_idx;
+        self.on_call();
+        if !self.rules.allow_kuikae {
+            self.kuikae_tiles = kuikae_tiles(tile, None);
+        }
+        true
+    }
+
+    /// 明槓を実行
+    fn execute_kan(&mut self, player_idx: usize) -> bool {
+        if !self.can_kan(player_idx) {
+            return false;
+        }
+
+        let tile = self.last_discard.unwrap();
+        let from = self.discarder();
+        let player = &mut self.players[player_idx];
+        let mut tiles = match player.hand.tiles_of_kind(&tile, 3) {
+            Some(tiles) => tiles,
+            None => return false,
+        };
+        tiles.push(tile);
+
+        // 鳴いた牌を手牌に加えてから、手牌の実際の牌と合わせて面子として抜き出す
+        player.hand.add_tile(tile);
+        let meld = crate::hand::Meld {
+            meld_type: crate::hand::MeldType::Kan(KanType::Open),
+            tiles,
+            is_open: true,
+            from: Some(from),
+            called_tile: Some(tile),
+        };
+
+        player.hand.add_meld(meld);
+        self.finish_call(player_idx);
+        self.current_player = player_idx;
+        self.on_call();
+        true
+    }
+
+    /// 暗槓を実行
+    fn execute_ankan(&mut self, player_idx: usize, tile: Tile) -> bool {
+        let player = &mut self.players[player_idx];
+        let tiles = match player.hand.tiles_of_kind(&tile, 4) {
+            Some(tiles) => tiles,
+            None => return false,
+        };
+
+        let meld = crate::hand::Meld {
+            meld_type: crate::hand::MeldType::Kan(KanType::Closed),
+            tiles,
+            is_open: false,
+            from: None,
+            called_tile: None,
+        };
+
+        player.hand.add_meld(meld);
+        true
+    }
+
+    /// 打牌を鳴いた後の共通処理（河の牌に鳴いたプレイヤーを記録して受付を閉じる）
+    fn finish_call(&mut self, caller: usize) {
+        self.record_missed_wins();
+        let discarder = self.discarder();
+        if let Some(discard) = self.players[discarder].discards.last_mut() {
+            discard.called_by = Some(caller);
+        }
+        self.last_discard = None;
+        self.claim_window = None;
+    }
+
+    /// 鳴き（暗槓を含む）で全員の一発と第一巡が消える
+    fn on_call(&mut self) {
+        self.call_made = true;
+        for player in &mut self.players {
+            player.clear_ippatsu();
+        }
+    }
+
+    /// リーチを宣言（鳴きのない第一打ならダブル立直）
+    pub fn declare_riichi(&mut self, player_idx: usize) -> bool {
+        self.apply(Action::Riichi { player: player_idx }).is_ok()
+    }
+
+    /// 和了の支払いを計算して点数に反映（供託は和了者が回収）
+    fn settle_win(&mut self, winner: usize, loser: Option<usize>, result: &ScoringResult) -> Payment {
+        self.settle(winner, loser, result, self.honba, self.riichi_sticks)
+    }
+
+    fn settle(
+        &mut self,
+        winner: usize,
+        loser: Option<usize>,
+        result: &ScoringResult,
+        honba: u32,
+        riichi_sticks: u32,
+    ) -> Payment {
+        let payment = self.payment(result.base_points, winner, loser, honba, riichi_sticks);
+        self.apply_payment(&payment);
+        payment
+    }
+
+    /// 人数に応じた支払いの計算
+    fn payment(&self, base_points: u32, winner: usize, loser: Option<usize>, honba: u32, riichi_sticks: u32) -> Payment {
+        if self.rules.is_sanma() {
+            let tsumo = self.rules.sanma_tsumo;
+            ScoringEngine::calculate_sanma_payment(base_points, winner, self.dealer, loser, honba, riichi_sticks, tsumo)
+        } else {
+            ScoringEngine::calculate_payment(base_points, winner, self.dealer, loser, honba, riichi_sticks)
+        }
+    }
+
+    /// 直前の打牌をしたプレイヤー（打牌で手番は次に進んでいる）
+    fn discarder(&self) -> usize {
+        let count = self.player_count();
+        (self.current_player + count - 1) % count
+    }
+
+    /// ツモ和了の判定と点数計算（和了形かつ役あり）
+    fn score_tsumo(&self, player_idx: usize) -> Option<(Tile, ScoringResult)> {
+        if self.phase != Phase::AwaitingDiscard || player_idx != self.current_player {
+            return None;
+        }
+
+        let tile = self.last_draw?;
+        let mut hand = self.players[player_idx].hand.clone();
+        if hand.tile_count() % 3 != 2 || !hand.remove_tile(&tile) {
+            return None;
+        }
+
+        let context = self.win_context(player_idx, true);
+        ScoringEngine::calculate_score(&hand, &tile, &context).map(|result| (tile, result))
+    }
+
+    /// ロン和了の判定と点数計算
+    fn score_ron(&self, player_idx: usize) -> Option<(Tile, ScoringResult)> {
+        let window = self.claim_window.as_ref()?;
+        if self.phase != Phase::ClaimWindow || player_idx == window.discarder || self.players[player_idx].is_furiten() {
+            return None;
+        }
+
+        let tile = window.tile;
+        let mut context = self.win_context(player_idx, false);
+        context.is_chankan = window.robbed_kan.is_some();
+        let result = ScoringEngine::calculate_score(&self.players[player_idx].hand, &tile, &context)?;
+
+        // 暗槓は国士無双でのみ槍槓できる
+        if window.robbed_kan == Some(KanType::Closed)
+            && !(self.rules.kokushi_rob_ankan && result.yaku.contains(&Yaku::Kokushi))
+        {
+            return None;
+        }
+        Some((tile, result))
+    }
+
+    /// ツモ和了できるか
+    pub fn can_tsumo(&self, player_idx: usize) -> bool {
+        self.score_tsumo(player_idx).is_some()
+    }
+
+    /// 直前の打牌でロン和了できるか
+    pub fn can_ron(&self, player_idx: usize) -> bool {
+        self.score_ron(player_idx).is_some()
+    }
+
+    /// ツモ和了を宣言し、支払いを反映して局を終了
+    pub fn declare_tsumo(&mut self, player_idx: usize) -> Option<HandResult> {
+        self.apply(Action::Tsumo { player: player_idx }).ok()?;
+        self.hand_result.clone()
+    }
+
+    /// ロン和了の精算（同じ牌で複数人が和了する場合はまとめて渡す）
+    fn settle_ron(&mut self, player_indices: &[usize]) -> Option<Event> {
+        let loser = self.claim_window.as_ref()?.discarder;
+
+        // 放銃者の下家から順に並べる
+        let mut winners: Vec<(usize, Tile, ScoringResult)> = self
+            .others(loser)
+            .into_iter()
+            .filter(|i| player_indices.contains(i))
+            .filter_map(|i| self.score_ron(i).map(|(tile, result)| (i, tile, result)))
+            .collect();
+        if winners.is_empty() {
+            return None;
+        }
+        if self.rules.multiple_ron == MultipleRon::HeadBump {
+            winners.truncate(1);
+        }
+
+        let (honba, riichi_sticks) = (self.honba, self.riichi_sticks);
+        let mut wins = Vec::new();
+        for (order, (winner, winning_tile, scoring)) in winners.into_iter().enumerate() {
+            // 積み棒と供託は放銃者に最も近い和了者が受け取る
+            let payment = if order == 0 {
+                self.settle(winner, Some(loser), &scoring, honba, riichi_sticks)
+            } else {
+                self.settle(winner, Some(loser), &scoring, 0, 0)
+            };
+            wins.push(WinResult {
+                winner,
+                loser: Some(loser),
+                winning_tile,
+                scoring,
+                payment,
+            });
+        }
+
+        Some(self.finish_hand(HandResult::Win(wins)))
+    }
+
+    /// 点数移動を反映
+    fn apply_payment(&mut self, payment: &Payment) {
+        for (i, player) in self.players.iter_mut().enumerate() {
+            player.add_score(payment.delta(i));
+        }
+        if payment.riichi_deposit > 0 {
+            self.riichi_sticks = 0;
+        }
+    }
+
+    /// 指定プレイヤーの自風
+    pub fn seat_wind(&self, player_idx: usize) -> Honor {
+        let count = self.player_count();
+        match (player_idx + count - self.dealer) % count {
+            0 => Honor::Ton,
+            1 => Honor::Nan,
+            2 => Honor::Shaa,
+            _ => Honor::Pei,
+        }
+    }
+
+    /// 和了時の状況を作成
+    pub fn win_context(&self, player_idx: usize, is_tsumo: bool) -> WinContext {
+        let player = &self.players[player_idx];
+        let mut context = WinContext::new(is_tsumo, self.seat_wind(player_idx), self.round_wind);
+        context.is_riichi = player.is_riichi;
+        context.is_double_riichi = player.double_riichi;
+        context.is_ippatsu = player.ippatsu;
+        context.is_last_tile = self.wall.is_empty() && !(is_tsumo && self.rinshan_draw);
+        context.is_rinshan = is_tsumo && self.rinshan_draw;
+        context.is_first_turn = player.discards.is_empty() && !self.call_made;
+        context.dora_indicators = self.wall.dora_indicators().to_vec();
+        context.kita = player.kita;
+        context.rules = self.rules.clone();
+        if player.is_riichi {
+            context.ura_dora_indicators = self.wall.ura_dora_indicators().to_vec();
+        }
+        context
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.phase == Phase::GameOver
+    }
+
+    /// 終局後の順位と最終ポイント（残った供託はトップへ）
+    pub fn match_result(&self) -> Option<MatchResult> {
+        if !self.is_game_over() {
+            return None;
+        }
+        Some(MatchResult::new(&self.players, self.riichi_sticks, &self.rules))
+    }
+
+    /// 局の表示（例: 東1局 0本場）
+    pub fn get_round_string(&self) -> String {
+        let wind = match self.round_wind {
+            Honor::Ton => "東",
+            Honor::Nan => "南",
+            Honor::Shaa => "西",
+            _ => "北",
+        };
+        format!("{}{}局 {}本場", wind, self.round, self.honba)
+    }
+
+    pub fn get_wall_count(&self) -> usize {
+        self.wall.remaining()
+    }
+
+    pub fn get_dora_indicators(&self) -> &[Tile] {
+        self.wall.dora_indicators()
+    }
+
+    /// 指定プレイヤーから見えている牌（全員の河・他家の副露・ドラ表示牌）
+    pub fn get_visible_tiles(&self, viewer: usize) -> Vec<Tile> {
+        let mut visible = self.wall.dora_indicators().to_vec();
+        for (i, player) in self.players.iter().enumerate() {
+            // 鳴かれた牌は副露側で数える
+            visible.extend(player.discards.iter().filter(|d| !d.is_called()).map(|d| d.tile));
+            visible.extend((0..player.kita).map(|_| Tile::new_honor(Honor::Pei)));
+            if i != viewer {
+                for meld in player.hand.get_melds() {
+                    visible.extend(meld.tiles.iter().copied());
+                }
+            }
+        }
+        visible
+    }
+
+    /// 指定プレイヤーの有効牌と残り枚数
+    pub fn get_ukeire(&self, player_idx: usize) -> Vec<Ukeire> {
+        let mut visible = self.get_visible_tiles(player_idx);
+        // 三人麻雀で使わない萬子の2〜8は残り0枚として扱う
+        if self.rules.is_sanma() {
+            for value in 2..=8 {
+                visible.extend([Tile::new_number(Suit::Man, value, false); 4]);
+            }
+        }
+        self.players[player_idx].hand.ukeire(&visible)
+    }
+
+    pub fn get_game_state_string(&self) -> String {
+        let mut result = String::new();
+        
+        result.push_str(&format!("{} 供託{} | サイコロ {}+{} | Wall: {} tiles\n", self.get_round_string(), self.riichi_sticks, self.dice[0], self.dice[1], self.wall.remaining()));
+        result.push_str(&format!("Dora indicators: {}\n", 
+            self.wall.dora_indicators().iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" ")));
+        
+        for (i, player) in self.players.iter().enumerate() {
+            let marker = if i == self.current_player { ">" } else { " " };
+            let dealer_mark = if player.is_dealer { "親" } else { " " };
+            result.push_str(&format!("{}{} {} ({}点): {}\n", 
+                marker, dealer_mark, player.name, player.score, player.get_hand_string()));
+            
+            if !player.discards.is_empty() {
+                result.push_str(&format!("  河: {}\n", player.get_discards_string()));
+            }
+            if player.kita > 0 {
+                result.push_str(&format!("  北抜き: {}枚\n", player.kita));
+            }
+        }
+        
+        if let Some(tile) = self.last_discard {
+            result.push_str(&format!("Last discard: {}\n", tile));
+        }
+        
+        result
+    }
+
+    /// 局の結果を文字列で取得（局が終わっていなければ空文字列）
+    pub fn get_hand_result_string(&self) -> String {
+        let mut result = String::new();
+
+        if let Some(HandResult::Abort(reason)) = &self.hand_result {
+            let name = match reason {
+                AbortReason::KyuushuKyuuhai(player) => format!("九種九牌（{}）", self.players[*player].name),
+                AbortReason::SuufonRenda => "四風連打".to_string(),
+                AbortReason::SuuchaRiichi => "四家立直".to_string(),
+                AbortReason::Suukaikan => "四開槓".to_string(),
+                AbortReason::Sanchahou => "三家和".to_string(),
+            };
+            result.push_str(&format!("途中流局: {}\n", name));
+        }
+
+        if let Some(HandResult::Draw(draw)) = &self.hand_result {
+            let names = |players: &[usize]| {
+                players.iter().map(|&i| self.players[i].name.clone()).collect::<Vec<_>>().join(" ")
+            };
+            result.push_str(&format!("流局 テンパイ: {}\n", names(&draw.tenpai)));
+            if !draw.nagashi.is_empty() {
+                result.push_str(&format!("流し満貫: {}\n", names(&draw.nagashi)));
+            }
+        }
+
+        if let Some(HandResult::Win(wins)) = &self.hand_result {
+            for win in wins {
+                let how = match win.loser {
+                    Some(loser) => format!("ロン（放銃: {}）", self.players[loser].name),
+                    None => "ツモ".to_string(),
+                };
+                result.push_str(&format!(
+                    "{} {} {}: {}飜{}符 {}点 {:?}\n",
+                    self.players[win.winner].name,
+                    how,
+                    win.winning_tile,
+                    win.scoring.han,
+                    win.scoring.fu,
+                    win.scoring.total_points,
+                    win.scoring.yaku
+                ));
+            }
+        }
+
+        result
+    }
+}
+
+/// 暗槓しても待ちが変わらないか（ツモ牌を除いた13枚の待ちと比べる）
+fn keeps_waits_after_ankan(hand: &Hand, tile: Tile) -> bool {
+    let Some(tiles) = hand.tiles_of_kind(&tile, 4) else {
+        return false;
+    };
+    let mut before = hand.clone();
+    before.remove_tile(&tile);
+    let mut after = hand.clone();
+    after.add_meld(Meld {
+        meld_type: MeldType::Kan(KanType::Closed),
+        tiles,
+        is_open: false,
+        from: None,
+        called_tile: None,
+    });
+    let waits = before.waits();
+    !waits.is_empty() && waits == after.waits()
+}
+
+/// 鳴いた牌に対して喰い替えとなる牌（ポンは pattern なし）
+///
+/// 鳴いた牌そのものと、チーで両端が埋まる形の反対側の筋の牌
+fn kuikae_tiles(called: Tile, chi_pattern: Option<usize>) -> Vec<Tile> {
+    let mut tiles = vec![called.normalized()];
+    if let TileType::Number { suit, value } = called.tile_type {
+        let suji = match chi_pattern {
+            Some(0) if value >= 4 => Some(value - 3),
+            Some(2) if value <= 6 => Some(value + 3),
+            _ => None,
+        };
+        tiles.extend(suji.map(|v| Tile::new_number(suit, v, false)));
+    }
+    tiles
+}
+
+/// 場風の順番（東=0）
+fn wind_index(wind: Honor) -> usize {
+    match wind {
+        Honor::Ton => 0,
+        Honor::Nan => 1,
+        Honor::Shaa => 2,
+        _ => 3,
+    }
+}
+
+/// 次の場風
+fn next_wind(wind: Honor) -> Honor {
+    match wind {
+        Honor::Ton => Honor::Nan,
+        Honor::Nan => Honor::Shaa,
+        Honor::Shaa => Honor::Pei,
+        _ => Honor::Ton,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Discard;
+    use crate::rules::GameLength;
+
+    /// 手牌を空にした対局（テストで手牌を指定する）
+    fn new_game() -> Game {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let mut game = Game::with_seed(names, RuleSet::default(), 1);
+        for player in &mut game.players {
+            player.hand = Hand::new();
+        }
+        game
+    }
+
+    fn hand_from(s: &str) -> Hand {
+        let mut hand = Hand::new();
+        for tile in Tile::parse_tiles(s).unwrap() {
+            hand.add_tile(tile);
+        }
+        hand
+    }
+
+    #[test]
     fn test_game_creation() {
         let names = vec!["Player1".to_string(), "Player2".to_string(), "Player3".to_string(), "Player4".to_string()];
-        let game = Game::new(names);
+        let game = Game::new(names, RuleSet::default());
         
         assert_eq!(game.players.len(), 4);
         assert!(game.players[0].is_dealer);
         assert_eq!(game.players[0].tile_count(), 14); // 親は14枚
         assert_eq!(game.players[1].tile_count(), 13); // 子は13枚
-        assert_eq!(game.dora_indicators.len(), 1);
+        assert_eq!(game.wall.dora_indicators().len(), 1);
     }
 
     #[test]
     fn test_tile_draw_and_discard() {
         let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
-        let mut game = Game::new(names);
+        let mut game = Game::new(names, RuleSet::default());
         
+        // 親は配牌14枚なのでツモらずに打牌
+        assert!(!game.current_player_draw());
+        let first_tile = game.get_current_player().hand.get_tiles()[0];
+        assert!(game.discard_tile(first_tile));
+        assert_eq!(game.current_player, 1); // Next player
+
+        // 鳴きの受付があれば全員パス
+        while let Some(window) = game.claim_window.clone() {
+            assert!(game.respond(window.pending[0], Claim::Pass));
+        }
+
         let initial_wall_count = game.get_wall_count();
         assert!(game.current_player_draw());
         assert_eq!(game.get_wall_count(), initial_wall_count - 1);
-        
-        let _player_tiles = game.get_current_player().get_hand_string();
+        assert_eq!(game.phase, Phase::AwaitingDiscard);
+    }
+
+    #[test]
+    fn test_apply_rejects_illegal_actions() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("1m 9p");
+        let one_man = Tile::new_number(Suit::Man, 1, false);
+
+        assert_eq!(game.apply(Action::Draw { player: 0 }).unwrap_err(), RuleError::WrongPhase(Phase::AwaitingDiscard));
+        assert_eq!(game.apply(Action::Discard { player: 1, tile: one_man }).unwrap_err(), RuleError::NotYourTurn);
+        assert_eq!(
+            game.apply(Action::Discard { player: 0, tile: Tile::new_honor(Honor::Chun) }).unwrap_err(),
+            RuleError::TileNotInHand
+        );
+        assert_eq!(game.apply(Action::Tsumo { player: 0 }).unwrap_err(), RuleError::CannotWin);
+
+        let events = game.apply(Action::Discard { player: 0, tile: one_man }).unwrap();
+        assert!(matches!(events[0], Event::Discarded { player: 0, .. }));
+        assert_eq!(game.phase, Phase::AwaitingDraw);
+
+        // 他家は誰でも鳴けるわけではない
+        assert_eq!(
+            game.apply(Action::Claim { player: 2, claim: Claim::Pon }).unwrap_err(),
+            RuleError::WrongPhase(Phase::AwaitingDraw)
+        );
+        assert_eq!(game.apply(Action::Discard { player: 1, tile: one_man }).unwrap_err(), RuleError::WrongPhase(Phase::AwaitingDraw));
+
+        let events = game.apply(Action::Draw { player: 1 }).unwrap();
+        assert!(matches!(events[0], Event::Drew { player: 1, .. }));
+    }
+
+    #[test]
+    fn test_win_context() {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let mut game = Game::new(names, RuleSet::default());
+
+        let context = game.win_context(0, true);
+        assert!(context.is_dealer());
+        assert!(context.is_first_turn);
+        assert_eq!(game.win_context(3, false).seat_wind, Honor::Pei);
+
         let first_tile = game.get_current_player().hand.get_tiles()[0];
-        
         assert!(game.discard_tile(first_tile));
-        assert_eq!(game.current_player, 1); // Next player
+        assert!(!game.win_context(0, false).is_first_turn);
+        assert!(game.win_context(1, true).is_first_turn);
+
+        game.on_call();
+        assert!(!game.win_context(1, true).is_first_turn);
+
+        // 裏ドラはリーチ者にのみ公開
+        assert_eq!(game.win_context(1, false).dora_indicators, game.wall.dora_indicators());
+        assert!(game.win_context(1, false).ura_dora_indicators.is_empty());
+        game.players[1].is_riichi = true;
+        assert_eq!(game.win_context(1, false).ura_dora_indicators.len(), 1);
+    }
+
+    #[test]
+    fn test_settle_win() {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let mut game = Game::new(names, RuleSet::default());
+        game.honba = 1;
+        game.riichi_sticks = 1;
+        game.players[2].score -= 1000; // リーチ棒の供託分
+
+        let result = ScoringResult {
+            han: 1,
+            fu: 30,
+            fu_items: Vec::new(),
+            yaku: vec![crate::scoring::Yaku::Riichi],
+            dora: 0,
+            ura_dora: 0,
+            aka_dora: 0,
+            kita: 0,
+            base_points: 240,
+            total_points: 1000,
+        };
+        game.settle_win(2, Some(1), &result);
+
+        assert_eq!(game.players[2].score, 25000 - 1000 + 1300 + 1000);
+        assert_eq!(game.players[1].score, 25000 - 1300);
+        assert_eq!(game.riichi_sticks, 0);
+        let total: i32 = game.players.iter().map(|p| p.score).sum();
+        assert_eq!(total + game.riichi_sticks as i32 * 1000, 100000);
+    }
+
+    #[test]
+    fn test_declare_tsumo() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("123m 456m 789p 345s 5s 5s");
+        game.players[0].discards.push(Discard { tile: Tile::new_honor(Honor::Pei), called_by: None, tsumogiri: false, riichi: false }); // 天和にしない
+        game.last_draw = Some(Tile::new_number(Suit::Sou, 5, false));
+
+        assert!(!game.can_tsumo(1));
+        assert!(game.can_tsumo(0));
+        let result = game.declare_tsumo(0).unwrap();
+        let HandResult::Win(wins) = result else {
+            panic!("ツモ和了のはず");
+        };
+        assert_eq!(wins.len(), 1);
+        assert!(wins[0].scoring.yaku.contains(&crate::scoring::Yaku::Tsumo));
+        assert_eq!(game.players[0].score, 25000 + wins[0].payment.delta(0));
+        assert_eq!(game.phase, Phase::HandOver);
+
+        // 局が終わった後は宣言できない
+        assert!(game.declare_tsumo(0).is_none());
+    }
+
+    #[test]
+    fn test_declare_ron_rejects_no_yaku() {
+        let mut game = new_game();
+        let four_sou = Tile::new_number(Suit::Sou, 4, false);
+        game.players[0].hand = hand_from("4s");
+        game.players[1].hand = hand_from("123m 456m 789p 35s 55s");
+        assert!(game.discard_tile(four_sou));
+
+        // 役なし（門前ロンのみ）
+        assert!(!game.can_ron(1));
+        assert!(!game.respond(1, Claim::Ron));
+        // 自分の打牌ではロンできない
+        assert!(!game.can_ron(0));
+    }
+
+    #[test]
+    fn test_double_ron_and_head_bump() {
+        let setup = |rules: RuleSet| {
+            let mut game = new_game();
+            game.rules = rules;
+            game.honba = 1;
+            game.riichi_sticks = 2;
+            game.players[0].hand = hand_from("4s");
+            for i in [1, 2] {
+                game.players[i].hand = hand_from("123m 456m 789p 35s 55s");
+                game.players[i].is_riichi = true;
+            }
+            assert!(game.discard_tile(Tile::new_number(Suit::Sou, 4, false)));
+            game
+        };
+
+        let mut game = setup(RuleSet::default());
+        assert!(game.respond(2, Claim::Ron));
+        assert!(game.respond(1, Claim::Ron));
+        assert_eq!(game.phase, Phase::HandOver);
+        let Some(HandResult::Win(wins)) = game.hand_result.clone() else {
+            panic!("double ron should win");
+        };
+        assert_eq!(wins.iter().map(|w| w.winner).collect::<Vec<_>>(), vec![1, 2]);
+        // 積み棒と供託は下家の和了者が受け取る
+        assert_eq!(wins[0].payment.riichi_deposit, 2000);
+        assert_eq!(wins[0].payment.delta(0), -(wins[0].scoring.total_points as i32 + 300));
+        assert_eq!(wins[1].payment.delta(0), -(wins[1].scoring.total_points as i32));
+
+        let mut game = setup(RuleSet { multiple_ron: MultipleRon::HeadBump, ..RuleSet::default() });
+        assert!(game.respond(1, Claim::Ron));
+        assert!(game.respond(2, Claim::Ron));
+        let Some(HandResult::Win(wins)) = game.hand_result.clone() else {
+            panic!("ron should win");
+        };
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].winner, 1);
+        assert_eq!(game.players[2].score, 25000);
+    }
+
+    #[test]
+    fn test_furiten_rejects_ron_for_shimocha() {
+        let mut game = new_game();
+        let four_sou = Tile::new_number(Suit::Sou, 4, false);
+        game.players[0].hand = hand_from("4s");
+        game.players[1].hand = hand_from("123m 456m 789p 35s 55s");
+        game.players[1].is_riichi = true;
+        assert!(game.discard_tile(four_sou));
+        assert!(game.can_ron(1));
+
+        // 下家は見逃した直後にツモるが、リーチ後の見逃しでフリテンになる
+        assert!(game.respond(1, Claim::Pass));
+        assert!(game.players[1].riichi_furiten);
+        assert!(game.current_player_draw());
+        let drawn = game.last_draw.unwrap();
+        assert!(game.discard_tile(drawn));
+        assert!(game.players[1].riichi_furiten);
+
+        // 同じ待ちの牌が出てもロンできない
+        game.players[1].hand = hand_from("123m 456m 789p 35s 55s");
+        for _ in 0..2 {
+            assert!(game.current_player_draw());
+            let drawn = game.last_draw.unwrap();
+            assert!(game.discard_tile(drawn));
+            while let Some(window) = game.claim_window.clone() {
+                assert!(game.respond(window.pending[0], Claim::Pass));
+            }
+        }
+        assert!(game.current_player_draw());
+        game.players[0].hand.add_tile(four_sou);
+        assert!(game.discard_tile(four_sou));
+        assert!(!game.can_ron(1));
+    }
+
+    #[test]
+    fn test_furiten_rejects_ron() {
+        let mut game = new_game();
+        let four_sou = Tile::new_number(Suit::Sou, 4, false);
+        game.players[0].hand = hand_from("4s");
+        game.players[1].hand = hand_from("9m");
+        game.players[2].hand = hand_from("123m 456m 789p 35s 55s");
+        game.players[2].is_riichi = true;
+        assert!(game.discard_tile(four_sou));
+        assert!(game.can_ron(2));
+
+        // 受付中は次のツモができない
+        assert!(!game.current_player_draw());
+
+        // 見逃して次の打牌へ進むとフリテン
+        assert!(game.respond(2, Claim::Pass));
+        assert!(game.claim_window.is_none());
+        assert!(game.current_player_draw());
+        assert!(game.discard_tile(game.last_draw.unwrap()));
+        assert!(game.players[2].riichi_furiten);
+        assert!(!game.can_ron(2));
+
+        // 自分の打牌を挟んでもリーチ後の見逃しは解除されない
+        assert!(game.current_player_draw());
+        assert!(game.discard_tile(game.last_draw.unwrap()));
+        assert!(game.current_player_draw());
+        game.players[3].hand.add_tile(four_sou);
+        assert!(game.discard_tile(four_sou));
+        assert!(!game.can_ron(2));
+    }
+
+    #[test]
+    fn test_claim_priority() {
+        let mut game = new_game();
+        let five_pin = Tile::new_number(Suit::Pin, 5, false);
+        game.players[0].hand = hand_from("5p");
+        game.players[1].hand = hand_from("46p 19m");
+        game.players[2].hand = hand_from("55p 19m");
+        game.players[3].hand = hand_from("123m 456m 789m 34p 99p");
+        game.players[3].is_riichi = true;
+        assert!(game.discard_tile(five_pin));
+
+        let window = game.claim_window.clone().unwrap();
+        assert_eq!(window.pending, vec![1, 2, 3]);
+        assert_eq!(game.available_claims(1), vec![Claim::Chi(1)]);
+        assert_eq!(game.available_claims(3), vec![Claim::Ron]);
+
+        // チーは受け付けてもまだ処理されない
+        assert!(!game.respond(1, Claim::Pon));
+        assert!(game.respond(1, Claim::Chi(1)));
+        assert!(!game.current_player_draw());
+
+        // ポンがチーより優先
+        assert!(game.respond(2, Claim::Pon));
+        assert!(game.respond(3, Claim::Pass));
+        assert!(game.claim_window.is_none());
+        assert_eq!(game.current_player, 2);
+        assert_eq!(game.players[2].hand.get_melds().len(), 1);
+        assert_eq!(game.players[0].discards[0].called_by, Some(2));
+        let meld = &game.players[2].hand.get_melds()[0];
+        assert_eq!((meld.from, meld.called_tile), (Some(0), Some(five_pin)));
+        assert!(game.get_visible_tiles(1).iter().filter(|&&t| t == five_pin).count() == 3);
+    }
+
+    #[test]
+    fn test_claim_ron_over_pon() {
+        let mut game = new_game();
+        let five_pin = Tile::new_number(Suit::Pin, 5, false);
+        game.players[0].hand = hand_from("5p");
+        game.players[2].hand = hand_from("55p 19m");
+        game.players[3].hand = hand_from("123m 456m 789m 34p 99p");
+        game.players[3].is_riichi = true;
+        assert!(game.discard_tile(five_pin));
+
+        assert_eq!(game.claim_window.as_ref().unwrap().pending, vec![2, 3]);
+        assert!(game.respond(3, Claim::Ron));
+        assert!(game.respond(2, Claim::Pon));
+        let Some(HandResult::Win(wins)) = &game.hand_result else {
+            panic!("ron should win");
+        };
+        assert_eq!(wins[0].winner, 3);
+        assert!(game.players[2].hand.get_melds().is_empty());
+    }
+
+    #[test]
+    fn test_visible_tiles_and_ukeire() {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let mut game = Game::new(names, RuleSet::default());
+
+        let first_tile = game.get_current_player().hand.get_tiles()[0];
+        assert!(game.discard_tile(first_tile));
+
+        let visible = game.get_visible_tiles(1);
+        assert_eq!(visible.len(), game.wall.dora_indicators().len() + 1);
+        assert!(visible.contains(&first_tile));
+
+        // 残り枚数は4枚を超えない
+        for ukeire in game.get_ukeire(1) {
+            assert!(ukeire.remaining <= 4);
+        }
+    }
+
+    /// 指定したプレイヤーの和了で局を終える（点数移動なし）
+    fn finish_with_win(game: &mut Game, winner: usize) {
+        let win = WinResult {
+            winner,
+            loser: None,
+            winning_tile: Tile::new_honor(Honor::Chun),
+            scoring: ScoringResult {
+                han: 1,
+                fu: 30,
+                fu_items: Vec::new(),
+                yaku: Vec::new(),
+                dora: 0,
+                ura_dora: 0,
+                aka_dora: 0,
+                kita: 0,
+                base_points: 240,
+                total_points: 1000,
+            },
+            payment: Payment { winner, transfers: Vec::new(), riichi_deposit: 0 },
+        };
+        game.finish_hand(HandResult::Win(vec![win]));
+    }
+
+    #[test]
+    fn test_dealer_renchan_and_rotation() {
+        let mut game = new_game();
+        game.riichi_sticks = 1;
+
+        // 親の和了で連荘・本場加算
+        finish_with_win(&mut game, 0);
+        assert_eq!(game.phase, Phase::HandOver);
+        assert!(matches!(game.next_hand().unwrap()[0], Event::HandStarted { round: 1, honba: 1, dealer: 0, .. }));
+        assert_eq!(game.get_round_string(), "東1局 1本場");
+        assert_eq!(game.current_player, 0);
+        assert_eq!(game.players[0].tile_count(), 14);
+        assert_eq!(game.riichi_sticks, 1); // 供託は持ち越し
+
+        // 子の和了で親が流れ、本場はリセット
+        finish_with_win(&mut game, 2);
+        game.next_hand().unwrap();
+        assert_eq!(game.get_round_string(), "東2局 0本場");
+        assert_eq!(game.dealer, 1);
+        assert!(game.players[1].is_dealer && !game.players[0].is_dealer);
+        assert_eq!(game.seat_wind(1), Honor::Ton);
+
+        // 局の途中では次局に進めない
+        assert_eq!(game.next_hand().unwrap_err(), RuleError::WrongPhase(Phase::AwaitingDiscard));
+    }
+
+    #[test]
+    fn test_all_last_ends_match() {
+        let mut game = new_game();
+        game.round_wind = Honor::Nan;
+        game.round = 4;
+        game.dealer = 3;
+        game.players[1].score = 40000;
+        game.players[0].score = 10000;
+
+        finish_with_win(&mut game, 1);
+        assert!(game.is_game_over());
+        let result = game.match_result().unwrap();
+        assert_eq!(result.standings[0].player, 1);
+        assert_eq!(result.standings[3].player, 0);
+
+        // 親がトップなら和了り止め、そうでなければ続行
+        let mut game = new_game();
+        game.round_wind = Honor::Nan;
+        game.round = 4;
+        game.dealer = 3;
+        game.players[3].score = 35000;
+        game.players[0].score = 15000;
+        finish_with_win(&mut game, 3);
+        assert!(game.is_game_over());
+
+        game.phase = Phase::AwaitingDiscard;
+        game.players[1].score = 40000;
+        game.players[2].score = 10000;
+        finish_with_win(&mut game, 3);
+        assert_eq!(game.phase, Phase::HandOver);
+    }
+
+    #[test]
+    fn test_west_extension() {
+        let mut game = new_game();
+        game.round_wind = Honor::Nan;
+        game.round = 4;
+        game.dealer = 3;
+
+        // 誰も返し点に届かなければ西入
+        finish_with_win(&mut game, 1);
+        assert_eq!(game.phase, Phase::HandOver);
+        game.next_hand().unwrap();
+        assert_eq!(game.get_round_string(), "西1局 0本場");
+
+        // 延長戦は返し点に届いた時点で終了
+        game.players[2].score = 31000;
+        game.players[0].score = 19000;
+        finish_with_win(&mut game, 2);
+        assert!(game.is_game_over());
+
+        // 延長なしのルールではオーラスで終了
+        let mut game = new_game();
+        game.rules.west_extension = false;
+        game.round_wind = Honor::Nan;
+        game.round = 4;
+        game.dealer = 3;
+        finish_with_win(&mut game, 1);
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn test_east_only_and_tobi() {
+        let mut game = new_game();
+        game.rules.length = GameLength::EastOnly;
+        game.round = 4;
+        game.dealer = 3;
+        finish_with_win(&mut game, 1);
+        assert_eq!(game.phase, Phase::HandOver);
+        game.next_hand().unwrap();
+        assert_eq!(game.get_round_string(), "南1局 0本場"); // 東風戦の延長
+
+        // 飛びで終了
+        game.players[0].score = -100;
+        finish_with_win(&mut game, 1);
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn test_rule_set_threaded_into_game() {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let rules = RuleSet { starting_score: 30000, ..RuleSet::m_league() };
+        let mut game = Game::new(names, rules);
+        assert!(game.players.iter().all(|p| p.score == 30000));
+        assert!(game.win_context(0, true).rules.multiple_ron == MultipleRon::HeadBump);
+
+        // 飛びなしならマイナスでも続行
+        for player in &mut game.players {
+            player.hand = Hand::new();
+        }
+        game.players[0].score = -100;
+        finish_with_win(&mut game, 1);
+        assert_eq!(game.phase, Phase::HandOver);
+    }
+
+    /// 山を空にして親が打牌すれば荒牌流局
+    fn draw_game(hands: [&str; 4]) -> Game {
+        let mut game = new_game();
+        for (player, hand) in game.players.iter_mut().zip(hands) {
+            player.hand = hand_from(hand);
+        }
+        while game.wall.draw().is_some() {}
+        game.apply(Action::Discard { player: 0, tile: Tile::new_number(Suit::Pin, 5, false) }).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_exhaustive_draw_noten_payments() {
+        let noten = "1357m 1357p 1357s 9m";
+
+        // 親のみテンパイ: 連荘して1本場
+        let mut game = draw_game(["123m 456m 789p 45s 99s 5p", noten, noten, noten]);
+        let Some(HandResult::Draw(draw)) = game.hand_result.clone() else {
+            panic!("流局のはず");
+        };
+        assert_eq!(draw.tenpai, vec![0]);
+        assert_eq!(game.players[0].score, 28000);
+        assert_eq!(game.players[1].score, 24000);
+        game.next_hand().unwrap();
+        assert_eq!(game.get_round_string(), "東1局 1本場");
+
+        // 2人テンパイは1500点ずつ、親ノーテンで親流れ（本場は加算）
+        let mut game = draw_game(["1357m 1357p 1357s 9m 5p", "123m 456m 789p 45s 99s", "123m 456m 789p 45s 99s", noten]);
+        assert_eq!(game.players.iter().map(|p| p.score).collect::<Vec<_>>(), vec![23500, 26500, 26500, 23500]);
+        game.next_hand().unwrap();
+        assert_eq!(game.get_round_string(), "東2局 1本場");
+        assert_eq!(game.dealer, 1);
+    }
+
+    #[test]
+    fn test_nagashi_mangan() {
+        let noten = "1357m 1357p 1357s 9m";
+        let hands = ["123m 456m 789p 45s 99s 5p", noten, noten, noten];
+
+        let mut game = new_game();
+        for (player, hand) in game.players.iter_mut().zip(hands) {
+            player.hand = hand_from(hand);
+        }
+        for tile in Tile::parse_tiles("1m 9p to").unwrap() {
+            game.players[1].discards.push(Discard { tile, called_by: None, tsumogiri: false, riichi: false });
+        }
+        while game.wall.draw().is_some() {}
+        game.apply(Action::Discard { player: 0, tile: Tile::new_number(Suit::Pin, 5, false) }).unwrap();
+
+        // 子の流し満貫はノーテン罰符の代わりに満貫ツモの支払い
+        let Some(HandResult::Draw(draw)) = &game.hand_result else {
+            panic!("流局のはず");
+        };
+        assert_eq!(draw.nagashi, vec![1]);
+        assert_eq!(game.players.iter().map(|p| p.score).collect::<Vec<_>>(), vec![21000, 33000, 23000, 23000]);
+
+        // 鳴かれた牌があれば不成立
+        game.players[1].discards[0].called_by = Some(2);
+        assert!(!game.players[1].is_nagashi());
+    }
+
+    #[test]
+    fn test_kyuushu_kyuuhai() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("19m 19p 19s to na sa 2345m 6m");
+        assert!(game.can_kyuushu_kyuuhai(0));
+        assert!(!game.can_kyuushu_kyuuhai(1));
+
+        game.rules.abortive_draws.kyuushu_kyuuhai = false;
+        assert_eq!(game.apply(Action::KyuushuKyuuhai { player: 0 }).unwrap_err(), RuleError::CannotAbort);
+        game.rules.abortive_draws.kyuushu_kyuuhai = true;
+
+        assert!(game.declare_kyuushu_kyuuhai(0));
+        assert!(matches!(game.hand_result, Some(HandResult::Abort(AbortReason::KyuushuKyuuhai(0)))));
+        game.next_hand().unwrap();
+        assert_eq!(game.get_round_string(), "東1局 1本場");
+
+        // 8種では宣言できない
+        game.players[0].hand = hand_from("19m 19p 19s to 2345m 66m");
+        assert!(!game.can_kyuushu_kyuuhai(0));
+    }
+
+    #[test]
+    fn test_suufon_renda() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("to 1357m 1357p 1357s 9m");
+        for i in 1..4 {
+            game.players[i].hand = hand_from("to 1357m 1357p 1357s");
+        }
+        let ton = Tile::new_honor(Honor::Ton);
+
+        game.apply(Action::Discard { player: 0, tile: ton }).unwrap();
+        for player in 1..4 {
+            assert!(game.hand_result.is_none());
+            game.apply(Action::Draw { player }).unwrap();
+            game.apply(Action::Discard { player, tile: ton }).unwrap();
+        }
+        assert!(matches!(game.hand_result, Some(HandResult::Abort(AbortReason::SuufonRenda))));
+        assert_eq!(game.phase, Phase::HandOver);
+    }
+
+    #[test]
+    fn test_riichi_stick_paid_when_declaration_passes() {
+        let one_pin = Tile::new_number(Suit::Pin, 1, false);
+        let setup = || {
+            let mut game = new_game();
+            game.players[0].hand = hand_from("123m 456m 789p 45s 99s 1p");
+            game
+        };
+
+        // 宣言牌が通ってから供託を支払う
+        let mut game = setup();
+        game.apply(Action::Riichi { player: 0 }).unwrap();
+        assert_eq!((game.players[0].score, game.riichi_sticks), (25000, 0));
+        let events = game.apply(Action::Discard { player: 0, tile: one_pin }).unwrap();
+        assert_eq!((game.players[0].score, game.riichi_sticks), (24000, 1));
+        assert!(matches!(events[0], Event::Discarded { player: 0, riichi: true, .. }));
+        assert!(events.contains(&Event::ScoresChanged { deltas: vec![-1000, 0, 0, 0] }));
+
+        // 宣言牌でロンされたらリーチは不成立で供託は払わない
+        let mut game = setup();
+        game.players[1].hand = hand_from("23p 456m 789m 55s hk hk hk");
+        game.apply(Action::Riichi { player: 0 }).unwrap();
+        game.apply(Action::Discard { player: 0, tile: one_pin }).unwrap();
+        game.apply(Action::Claim { player: 1, claim: Claim::Ron }).unwrap();
+        let Some(HandResult::Win(wins)) = &game.hand_result else { panic!("no win") };
+        assert_eq!(wins[0].payment.riichi_deposit, 0);
+        assert_eq!(game.riichi_sticks, 0);
+        assert_eq!(game.players[0].score + game.players[1].score, 50000);
+
+        // ツモ山が人数分残っていなければリーチできない
+        let mut game = setup();
+        while game.wall.remaining() >= 4 {
+            game.wall.draw();
+        }
+        assert!(!game.can_riichi(0));
+        assert_eq!(game.apply(Action::Riichi { player: 0 }).unwrap_err(), RuleError::CannotRiichi);
+    }
+
+    #[test]
+    fn test_suucha_riichi() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("123m 456m 789p 45s 99s 1p");
+        for i in 1..4 {
+            game.players[i].is_riichi = true;
+        }
+        game.riichi_sticks = 3;
+
+        game.apply(Action::Riichi { player: 0 }).unwrap();
+        game.apply(Action::Discard { player: 0, tile: Tile::new_number(Suit::Pin, 1, false) }).unwrap();
+        assert!(matches!(game.hand_result, Some(HandResult::Abort(AbortReason::SuuchaRiichi))));
+        assert_eq!(game.riichi_sticks, 4); // 供託は次局へ持ち越し
+    }
+
+    #[test]
+    fn test_suukaikan() {
+        let kan = |s: &str| Meld {
+            meld_type: MeldType::Kan(KanType::Open),
+            tiles: Tile::parse_tiles(s).unwrap(),
+            is_open: true,
+            from: None,
+            called_tile: None,
+        };
+        let setup = |owners: [usize; 4]| {
+            let mut game = new_game();
+            game.players[0].hand = hand_from("1357m 1357p 1357s 9m 5p");
+            for (owner, tiles) in owners.into_iter().zip(["2222m", "4444p", "6666s", "8888s"]) {
+                game.players[owner].hand.add_meld(kan(tiles));
+            }
+            game.apply(Action::Discard { player: 0, tile: Tile::new_number(Suit::Pin, 5, false) }).unwrap();
+            game
+        };
+
+        let game = setup([1, 1, 2, 3]);
+        assert!(matches!(game.hand_result, Some(HandResult::Abort(AbortReason::Suukaikan))));
+
+        // 1人で4回カンした場合は続行（四槓子の可能性）
+        let game = setup([2, 2, 2, 2]);
+        assert!(game.hand_result.is_none());
+    }
+
+    #[test]
+    fn test_sanchahou() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("4s");
+        for i in 1..4 {
+            game.players[i].hand = hand_from("123m 456m 789p 35s 55s");
+            game.players[i].is_riichi = true;
+        }
+        assert!(game.discard_tile(Tile::new_number(Suit::Sou, 4, false)));
+        for i in 1..4 {
+            assert!(game.respond(i, Claim::Ron));
+        }
+        assert!(matches!(game.hand_result, Some(HandResult::Abort(AbortReason::Sanchahou))));
+        assert_eq!(game.players[0].score, 25000);
+    }
+
+    #[test]
+    fn test_kan_dora_timing() {
+        // 暗槓は即めくり、嶺上牌の分だけツモ山が減る
+        let mut game = new_game();
+        game.players[0].hand = hand_from("1111m 23m 456p 789s 99s");
+        let remaining = game.get_wall_count();
+        let events = game.apply(Action::Ankan { player: 0, tile: Tile::new_number(Suit::Man, 1, false) }).unwrap();
+        assert!(matches!(events[1], Event::DoraRevealed { .. }));
+        assert!(matches!(events[2], Event::Drew { player: 0, .. }));
+        assert_eq!(game.get_dora_indicators().len(), 2);
+        assert_eq!(game.get_wall_count(), remaining - 1);
+        assert_eq!(game.wall.rinshan_remaining(), 3);
+
+        // 明槓は打牌後にめくる
+        let mut game = new_game();
+        let five_pin = Tile::new_number(Suit::Pin, 5, false);
+        game.players[0].hand = hand_from("5p");
+        game.players[1].hand = hand_from("555p 19m");
+        game.apply(Action::Discard { player: 0, tile: five_pin }).unwrap();
+        let events = game.apply(Action::Claim { player: 1, claim: Claim::Kan }).unwrap();
+        assert!(!events.iter().any(|e| matches!(e, Event::DoraRevealed { .. })));
+        assert_eq!(game.get_dora_indicators().len(), 1);
+
+        let events = game.apply(Action::Discard { player: 1, tile: Tile::new_number(Suit::Man, 1, false) }).unwrap();
+        assert!(matches!(events[1], Event::DoraRevealed { .. }));
+        assert_eq!(game.get_dora_indicators().len(), 2);
+        assert_eq!(game.wall.ura_dora_indicators().len(), 2);
+    }
+
+    /// 5mをポンしていて手牌に4枚目がある状態
+    fn kakan_game() -> Game {
+        let mut game = new_game();
+        let five = Tile::new_number(Suit::Man, 5, false);
+        game.players[0].hand = hand_from("555m 5m 19p");
+        game.players[0].hand.add_meld(Meld {
+            meld_type: MeldType::Pon,
+            tiles: vec![five; 3],
+            is_open: true,
+            from: Some(3),
+            called_tile: Some(five),
+        });
+        game.players[1].hand = hand_from("123m 46m 789p 123s 99s");
+        game.players[1].is_riichi = true;
+        game
+    }
+
+    #[test]
+    fn test_kakan_and_chankan() {
+        let five = Tile::new_number(Suit::Man, 5, false);
+
+        let mut game = kakan_game();
+        assert_eq!(game.can_kakan(0), vec![five]);
+        let events = game.apply(Action::Kakan { player: 0, tile: five }).unwrap();
+        assert!(matches!(events[1], Event::ClaimWindowOpened { discarder: 0, .. }));
+        assert_eq!(game.available_claims(1), vec![Claim::Ron]);
+        assert!(game.respond(1, Claim::Ron));
+        let Some(HandResult::Win(wins)) = &game.hand_result else {
+            panic!("槍槓のはず");
+        };
+        assert!(wins[0].scoring.yaku.contains(&crate::scoring::Yaku::Chankan));
+        assert_eq!(wins[0].loser, Some(0));
+
+        // 見逃すとカンが成立して嶺上牌をツモり、見逃した側はフリテン
+        let mut game = kakan_game();
+        game.apply(Action::Kakan { player: 0, tile: five }).unwrap();
+        let events = game.apply(Action::Claim { player: 1, claim: Claim::Pass }).unwrap();
+        assert!(matches!(events.last(), Some(Event::Drew { player: 0, .. })));
+        assert_eq!(game.phase, Phase::AwaitingDiscard);
+        assert_eq!(game.players[0].hand.get_melds()[0].meld_type, MeldType::Kan(KanType::Added));
+        assert!(game.players[1].is_furiten());
+    }
+
+    #[test]
+    fn test_kokushi_robs_ankan() {
+        let one = Tile::new_number(Suit::Man, 1, false);
+        let setup = |kokushi_rob_ankan: bool| {
+            let mut game = new_game();
+            game.rules.kokushi_rob_ankan = kokushi_rob_ankan;
+            game.players[0].hand = hand_from("1111m 23m 456p 789s 99s");
+            game.players[1].hand = hand_from("9m 19p 19s to na sa pe hk ht cn cn");
+            game.players[2].hand = hand_from("23m 456m 789p 123s 99s");
+            game.players[2].is_riichi = true;
+            game.apply(Action::Ankan { player: 0, tile: one }).unwrap();
+            game
+        };
+
+        // 国士無双のみロンでき、通常の手は暗槓を槍槓できない
+        let mut game = setup(true);
+        assert_eq!(game.claim_window.as_ref().unwrap().pending, vec![1]);
+        assert!(game.respond(1, Claim::Ron));
+        let Some(HandResult::Win(wins)) = &game.hand_result else {
+            panic!("国士無双の槍槓のはず");
+        };
+        assert!(wins[0].scoring.yaku.contains(&crate::scoring::Yaku::Kokushi));
+
+        let game = setup(false);
+        assert!(game.claim_window.is_none());
+        assert_eq!(game.phase, Phase::AwaitingDiscard);
+    }
+
+    #[test]
+    fn test_kuikae() {
+        let five_pin = Tile::new_number(Suit::Pin, 5, false);
+        let two_pin = Tile::new_number(Suit::Pin, 2, false);
+        let setup = |hand: &str, allow_kuikae: bool| {
+            let mut game = new_game();
+            game.rules.allow_kuikae = allow_kuikae;
+            game.players[0].hand = hand_from("5p");
+            game.players[1].hand = hand_from(hand);
+            game.apply(Action::Discard { player: 0, tile: five_pin }).unwrap();
+            game
+        };
+
+        // 34pで5pをチーすると5pと筋の2pは打牌できない
+        let mut game = setup("34p 2p 5p 7m", false);
+        assert!(game.respond(1, Claim::Chi(0)));
+        assert_eq!(game.legal_discards(1), vec![Tile::new_number(Suit::Man, 7, false)]);
+        assert_eq!(game.apply(Action::Discard { player: 1, tile: two_pin }).unwrap_err(), RuleError::Kuikae);
+        assert_eq!(game.apply(Action::Discard { player: 1, tile: five_pin }).unwrap_err(), RuleError::Kuikae);
+        assert!(game.discard_tile(Tile::new_number(Suit::Man, 7, false)));
+        assert!(game.kuikae_tiles.is_empty());
+
+        // 喰い替えの牌しか残らないならチーできない
+        let game = setup("34p 2p", false);
+        assert!(game.claim_window.is_none());
+
+        // 喰い替えありのルール
+        let mut game = setup("34p 2p", true);
+        assert!(game.respond(1, Claim::Chi(0)));
+        assert!(game.discard_tile(two_pin));
+
+        // ポンした牌と同じ牌も打牌できない
+        let mut game = new_game();
+        game.players[0].hand = hand_from("5p");
+        game.players[2].hand = hand_from("555p 19m");
+        game.apply(Action::Discard { player: 0, tile: five_pin }).unwrap();
+        assert!(game.respond(2, Claim::Pon));
+        assert_eq!(game.apply(Action::Discard { player: 2, tile: five_pin }).unwrap_err(), RuleError::Kuikae);
+        assert_eq!(game.legal_discards(2).len(), 2);
+    }
+
+    #[test]
+    fn test_calls_with_red_fives() {
+        let five_pin = Tile::new_number(Suit::Pin, 5, false);
+        let red_five_pin = Tile::new_number(Suit::Pin, 5, true);
+
+        // 赤5の打牌をポンでき、面子には赤5がそのまま入る
+        let mut game = new_game();
+        game.players[0].hand = hand_from("5pr");
+        game.players[2].hand = hand_from("55p 19m");
+        assert!(game.discard_tile(red_five_pin));
+        assert_eq!(game.available_claims(2), vec![Claim::Pon]);
+        assert!(game.respond(2, Claim::Pon));
+        let meld = &game.players[2].hand.get_melds()[0];
+        assert_eq!(meld.called_tile, Some(red_five_pin));
+        assert_eq!(meld.tiles.iter().filter(|t| t.is_red).count(), 1);
+        assert_eq!(game.players[2].hand.get_tiles().len(), 2);
+
+        // 手牌の5pと赤5pで通常の5pをポンすると、赤5が面子に移る
+        let mut game = new_game();
+        game.players[0].hand = hand_from("5p");
+        game.players[2].hand = hand_from("5p 5pr 19m");
+        assert!(game.discard_tile(five_pin));
+        assert!(game.respond(2, Claim::Pon));
+        let meld = &game.players[2].hand.get_melds()[0];
+        assert_eq!(meld.tiles.iter().filter(|t| t.is_red).count(), 1);
+        assert!(!game.players[2].hand.get_tiles().iter().any(|t| t.is_red));
+
+        // 赤5の打牌を明槓できる
+        let mut game = new_game();
+        game.players[0].hand = hand_from("5pr");
+        game.players[2].hand = hand_from("555p 19m");
+        assert!(game.discard_tile(red_five_pin));
+        assert!(game.available_claims(2).contains(&Claim::Kan));
+
+        // 手牌の赤5を使うチー
+        let mut game = new_game();
+        game.players[0].hand = hand_from("4p");
+        game.players[1].hand = hand_from("5pr 6p 19m");
+        assert!(game.discard_tile(Tile::new_number(Suit::Pin, 4, false)));
+        assert_eq!(game.available_claims(1), vec![Claim::Chi(2)]);
+        assert!(game.respond(1, Claim::Chi(2)));
+        let meld = &game.players[1].hand.get_melds()[0];
+        assert!(meld.tiles.contains(&red_five_pin));
+        assert_eq!(game.players[1].hand.get_tiles().len(), 2);
+    }
+
+    #[test]
+    fn test_ankan_with_red_five() {
+        let mut game = new_game();
+        game.players[0].hand = hand_from("555m 5mr 456p 789p 11s 9s");
+        let five_man = Tile::new_number(Suit::Man, 5, false);
+        assert_eq!(game.can_ankan(0), vec![five_man]);
+
+        // 手番でなければ暗槓できない
+        assert!(game.can_ankan(1).is_empty());
+
+        // 赤ドラを含む実際の4枚が面子になる
+        assert!(game.apply(Action::Ankan { player: 0, tile: five_man }).is_ok());
+        let meld = &game.players[0].hand.get_melds()[0];
+        assert_eq!(meld.tiles.iter().filter(|t| t.is_red).count(), 1);
+        assert!(!game.players[0].hand.get_tiles().iter().any(|t| t.index() == five_man.index()));
+    }
+
+    #[test]
+    fn test_riichi_locks_discards_and_ankan() {
+        let one_man = Tile::new_number(Suit::Man, 1, false);
+        let setup = |hand: &str| {
+            let mut game = new_game();
+            game.players[0].hand = hand_from(hand);
+            game.players[0].draw_tile(one_man);
+            // 宣言牌は打牌済み
+            game.players[0].is_riichi = true;
+            game.players[0].riichi_turn = Some(0);
+            game.players[0].discards.push(Discard {
+                tile: Tile::new_honor(Honor::Pei),
+                called_by: None,
+                tsumogiri: false,
+                riichi: true,
+            });
+            game
+        };
+
+        // ツモ牌以外は打牌できない（理牌されていてもツモ牌を覚えている）
+        let mut game = setup("111m 456m 789p 23s 55s");
+        assert_eq!(game.legal_discards(0), vec![one_man]);
+        let two_sou = Tile::new_number(Suit::Sou, 2, false);
+        assert_eq!(game.apply(Action::Discard { player: 0, tile: two_sou }).unwrap_err(), RuleError::RiichiLocked);
+
+        // 待ちが変わらない暗槓はできる
+        assert_eq!(game.can_ankan(0), vec![one_man]);
+        assert!(game.apply(Action::Ankan { player: 0, tile: one_man }).is_ok());
+
+        // 1m4m5s待ちが1m4m待ちに変わる暗槓はできない
+        let game = setup("111m 23m 789p 456s 55s");
+        assert!(game.can_ankan(0).is_empty());
+    }
+
+    fn sanma_game() -> Game {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string()];
+        Game::with_seed(names, RuleSet::sanma(), 1)
+    }
+
+    #[test]
+    fn test_sanma_deal_and_rotation() {
+        let mut game = sanma_game();
+        assert_eq!(game.player_count(), 3);
+        assert!(game.players.iter().all(|p| p.score == 35000));
+        assert_eq!(game.players[0].tile_count(), 14);
+        assert_eq!(game.wall.remaining(), 108 - 18 - 13 * 3 - 1);
+        assert_eq!(game.wall.rinshan_remaining(), 8);
+
+        // 萬子は1と9のみ
+        let has_middle_man = game.players.iter().flat_map(|p| p.hand.get_tiles().iter()).any(|tile| {
+            matches!(tile.tile_type, TileType::Number { suit: Suit::Man, value: 2..=8 })
+        });
+        assert!(!has_middle_man);
+        assert_eq!(game.seat_wind(2), Honor::Shaa);
+
+        // 3局で次の場へ
+        game.round = 3;
+        game.dealer = 2;
+        finish_with_win(&mut game, 1);
+        game.next_hand().unwrap();
+        assert_eq!(game.dealer, 0);
+        assert_eq!(game.get_round_string(), "南1局 0本場");
+    }
+
+    #[test]
+    fn test_sanma_no_chi_and_kita() {
+        let mut game = sanma_game();
+        for player in &mut game.players {
+            player.hand = Hand::new();
+        }
+        let north = Tile::new_honor(Honor::Pei);
+
+        // 北を抜くと嶺上牌をツモり、抜きドラとして数える
+        game.players[0].hand = hand_from("3p pe 19m");
+        game.players[0].draw_tile(Tile::new_number(Suit::Sou, 9, false));
+        assert!(game.can_kita(0));
+        assert!(game.declare_kita(0));
+        assert_eq!(game.players[0].kita, 1);
+        assert!(!game.players[0].hand.get_tiles().contains(&north));
+        assert_eq!(game.players[0].tile_count(), 5);
+        assert_eq!(game.wall.rinshan_remaining(), 7);
+        assert!(game.rinshan_draw);
+        assert_eq!(game.win_context(0, true).kita, 1);
+        assert!(!game.can_kita(0));
+
+        // 下家でもチーはできない
+        game.players[1].hand = hand_from("12p 9s");
+        assert!(game.discard_tile(Tile::new_number(Suit::Pin, 3, false)));
+        assert!(game.claim_window.is_none());
+        assert_eq!(game.current_player, 1);
+    }
+
+    #[test]
+    fn test_sanma_fifth_kan_rejected() {
+        let kan = |s: &str| Meld {
+            meld_type: MeldType::Kan(KanType::Open),
+            tiles: Tile::parse_tiles(s).unwrap(),
+            is_open: true,
+            from: None,
+            called_tile: None,
+        };
+        let mut game = sanma_game();
+        for player in &mut game.players {
+            player.hand = Hand::new();
+        }
+        game.players[0].hand = hand_from("1111p 3333p 99s 5p");
+        game.players[0].hand.add_meld(Meld {
+            meld_type: MeldType::Pon,
+            tiles: Tile::parse_tiles("333p").unwrap(),
+            is_open: true,
+            from: Some(1),
+            called_tile: Some(Tile::new_number(Suit::Pin, 3, false)),
+        });
+        game.players[1].hand = hand_from("555p 9m");
+
+        // 3回までならカンできる
+        for tiles in ["2222s", "4444s", "6666s"] {
+            game.players[2].hand.add_meld(kan(tiles));
+        }
+        assert!(!game.can_ankan(0).is_empty());
+        assert!(!game.can_kakan(0).is_empty());
+
+        // 嶺上牌が残っていても5回目のカンはできない（1人で4回なので流局もしない）
+        game.players[2].hand.add_meld(kan("8888s"));
+        assert_eq!(game.wall.rinshan_remaining(), 8);
+        assert!(game.can_ankan(0).is_empty());
+        assert!(game.can_kakan(0).is_empty());
+        assert!(game.discard_tile(Tile::new_number(Suit::Pin, 5, false)));
+        assert!(game.hand_result.is_none());
+        assert!(!game.can_kan(1));
+        assert!(game.available_claims(1).contains(&Claim::Pon));
+    }
+
+    #[test]
+    fn test_sanma_payments() {
+        // ノーテン罰符は2000点
+        let mut game = sanma_game();
+        let noten = "1357s 1357p 1599m";
+        for (player, hand) in game.players.iter_mut().zip(["111m 456p 789p 11s 99s 5p", noten, noten]) {
+            player.hand = hand_from(hand);
+        }
+        while game.wall.draw().is_some() {}
+        game.apply(Action::Discard { player: 0, tile: Tile::new_number(Suit::Pin, 5, false) }).unwrap();
+        let Some(HandResult::Draw(draw)) = game.hand_result.clone() else {
+            panic!("expected exhaustive draw");
+        };
+        assert_eq!(draw.tenpai, vec![0]);
+        assert_eq!(draw.delta(0), 2000);
+        assert_eq!(draw.delta(1), -1000);
+
+        // ツモ損: 子の1飜30符ツモは親500・子300
+        let mut game = sanma_game();
+        let result = ScoringResult {
+            han: 1,
+            fu: 30,
+            fu_items: Vec::new(),
+            yaku: vec![crate::scoring::Yaku::Tsumo],
+            dora: 0,
+            ura_dora: 0,
+            aka_dora: 0,
+            kita: 0,
+            base_points: 240,
+            total_points: 1100,
+        };
+        let payment = game.settle_win(1, None, &result);
+        assert_eq!(payment.transfers.len(), 2);
+        assert_eq!(payment.delta(1), 800);
+    }
+
+    fn drain_wall(game: &Game) -> Vec<Tile> {
+        let mut wall = game.wall.clone();
+        std::iter::from_fn(|| wall.draw()).collect()
+    }
+
+    #[test]
+    fn test_seed_reproduces_wall_and_dice() {
+        let names = || vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let a = Game::with_seed(names(), RuleSet::default(), 42);
+        let b = Game::with_seed(names(), RuleSet::default(), 42);
+        let c = Game::with_seed(names(), RuleSet::default(), 43);
+
+        assert_eq!(a.seed, 42);
+        assert_eq!(a.dice, b.dice);
+        assert_eq!(a.wall.dora_indicators(), b.wall.dora_indicators());
+        assert_eq!(drain_wall(&a), drain_wall(&b));
+        assert_ne!(drain_wall(&a), drain_wall(&c));
+        for (pa, pb) in a.players.iter().zip(&b.players) {
+            assert_eq!(pa.get_hand_string(), pb.get_hand_string());
+        }
+        assert!(a.dice.iter().all(|d| (1..=6).contains(d)));
+
+        // 次の局も同じ山になる
+        let (mut a, mut b) = (a, b);
+        for game in [&mut a, &mut b] {
+            game.phase = Phase::HandOver;
+            game.next_hand().unwrap();
+        }
+        assert_eq!(drain_wall(&a), drain_wall(&b));
+
+        // プラットフォームによらず同じ配牌になる（WASMでも一致する）
+        let game = Game::with_seed(names(), RuleSet::default(), 0);
+        assert_eq!(game.players[0].get_hand_string(), "5m 8m 2p 2p 6p 9p 9p 7s 8s 9s sa pe hk  pe");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_event_log_records_whole_hand() {
+        let names = vec!["P1".to_string(), "P2".to_string(), "P3".to_string(), "P4".to_string()];
+        let mut game = Game::with_seed(names, RuleSet::default(), 7);
+
+        // 局の開始・配牌・ドラ表示牌・親の14枚目
+        let events = game.log().events();
+        assert!(matches!(events[0], Event::HandStarted { round: 1, dealer: 0, .. }));
+        for (i, event) in events[1..5].iter().enumerate() {
+            assert!(matches!(event, Event::Dealt { player, tiles } if *player == i && tiles.len() == 13));
+        }
+        assert!(matches!(events[5], Event::DoraRevealed { .. }));
+        assert!(matches!(events[6], Event::Drew { player: 0, .. }));
+
+        // 全員ツモ切り・パスで流局まで進める
+        while game.phase != Phase::HandOver && game.phase != Phase::GameOver {
+            match game.phase {
+                Phase::AwaitingDraw => assert!(game.current_player_draw()),
+                Phase::AwaitingDiscard => {
+                    let tile = game.players[game.current_player].hand.drawn_tile().unwrap();
+                    assert!(game.discard_tile(tile));
+                }
+                _ => {
+                    let player = game.claim_window.as_ref().unwrap().pending[0];
+                    assert!(game.respond(player, Claim::Pass));
+                }
+            }
+        }
+
+        // 打牌は全て記録され、最後は精算
+        let events = game.log().events();
+        let discards: usize = game.players.iter().map(|p| p.discards.len()).sum();
+        assert_eq!(events.iter().filter(|e| matches!(e, Event::Discarded { tsumogiri: true, .. })).count(), discards);
+        assert!(matches!(events[events.len() - 2], Event::HandEnded(_)));
+        let Some(Event::ScoresChanged { deltas }) = events.last() else { panic!("no payment") };
+        for (player, delta) in game.players.iter().zip(deltas) {
+            assert_eq!(player.score, 25000 + delta);
+        }
+
+        // 次の局の開始も続けて記録される
+        let before = game.log().len();
+        if game.phase == Phase::HandOver {
+            let started = game.next_hand().unwrap();
+            assert_eq!(&game.log().events()[before..], &started[..]);
+        }
+
+        let json = game.log().to_json().unwrap();
+        let log = GameLog::from_json(&json).unwrap();
+        assert_eq!((log.version, log.seed), (crate::game_log::LOG_VERSION, 7));
+        assert_eq!(&log, game.log());
+    }
+}
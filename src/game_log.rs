@@ -0,0 +1,85 @@
+//! 牌譜（対局で起きた出来事を順に追記するログ）
+//!
+//! リプレイ・通信対戦の同期・集計はこのログを読めばよい。シード値があれば山も再現できる。
+
+use crate::action::Event;
+use crate::rules::RuleSet;
+use serde::{Deserialize, Serialize};
+
+/// 牌譜の形式のバージョン（互換性のない変更で上げる）
+pub const LOG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameLog {
+    pub version: u32,
+    pub seed: u64, // 山・サイコロを決めたシード値
+    pub rules: RuleSet,
+    pub players: Vec<String>, // 起家からの席順
+    events: Vec<Event>,
+}
+
+impl GameLog {
+    pub fn new(seed: u64, rules: RuleSet, players: Vec<String>) -> Self {
+        Self {
+            version: LOG_VERSION,
+            seed,
+            rules,
+            players,
+            events: Vec::new(),
+        }
+    }
+
+    /// 出来事を末尾に追記（記録済みの出来事は変更できない）
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    /// 対応していないバージョンの牌譜はエラー
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let log: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if log.version != LOG_VERSION {
+            return Err(format!("Unsupported log version: {}", log.version));
+        }
+        Ok(log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::Tile;
+
+    #[test]
+    fn test_log_json_roundtrip() {
+        let mut log = GameLog::new(42, RuleSet::default(), vec!["P1".to_string(), "P2".to_string()]);
+        let tile = Tile::from_string("5pr").unwrap();
+        log.push(Event::Drew { player: 0, tile });
+        log.push(Event::Discarded { player: 0, tile, tsumogiri: true, riichi: false });
+        log.push(Event::ScoresChanged { deltas: vec![-1000, 1000] });
+
+        let json = log.to_json().unwrap();
+        assert!(json.contains(r#""version":1"#));
+        assert!(json.contains(r#""seed":42"#));
+        assert!(json.contains(r#"{"Drew":{"player":0,"tile":"5pr"}}"#));
+        assert_eq!(GameLog::from_json(&json).unwrap(), log);
+
+        let future = json.replace(r#""version":1"#, r#""version":99"#);
+        assert!(GameLog::from_json(&future).is_err());
+    }
+}
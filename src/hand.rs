@@ -1,17 +1,31 @@
-use crate::tile::Tile;
+use crate::tile::{Tile, TILE_KINDS};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+
+/// 么九牌（1・9・字牌）のインデックス
+const YAOCHU_INDICES: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
 
 #[derive(Debug, Clone)]
 pub struct Hand {
-    tiles: Vec<Tile>,
+    tiles: Vec<Tile>,     // 門前の牌（ツモ牌を含めて理牌済み）
     melds: Vec<Meld>,
+    drawn: Option<Tile>, // 直前にツモった牌（打牌するまで他の牌と区別する）
 }
 
-#[derive(Debug, Clone)]
+/// 槓子の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KanType {
+    Closed, // 暗槓
+    Open,   // 大明槓
+    Added,  // 加槓（ポンに4枚目を加える）
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MeldType {
-    Chi,    // 順子 (チー)
-    Pon,    // 刻子 (ポン)
-    Kan,    // 槓子 (カン)
+    Chi,          // 順子 (チー)
+    Pon,          // 刻子 (ポン)
+    Kan(KanType), // 槓子 (カン)
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +33,108 @@ pub struct Meld {
     pub meld_type: MeldType,
     pub tiles: Vec<Tile>,
     pub is_open: bool,
+    pub from: Option<usize>,        // 鳴いた牌を捨てたプレイヤー（暗槓はNone）
+    pub called_tile: Option<Tile>, // 鳴いた牌（横向きに置く牌）
+}
+
+/// 和了形を構成する面子の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetKind {
+    Sequence,         // 順子
+    ConcealedTriplet, // 暗刻
+    OpenTriplet,      // 明刻（ポン、またはロン牌で完成した刻子）
+    Kan,              // 槓子
+}
+
+/// 和了形を構成する面子
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandSet {
+    pub kind: SetKind,
+    pub tile: Tile,    // 順子は先頭の牌、刻子・槓子は構成牌
+    pub is_open: bool, // 副露した面子か
+}
+
+impl HandSet {
+    /// 面子の構成牌（槓子は4枚）
+    pub fn tiles(&self) -> Vec<Tile> {
+        match self.kind {
+            SetKind::Sequence => (0..3).map(|i| Tile::from_index(self.tile.index() + i)).collect(),
+            SetKind::ConcealedTriplet | SetKind::OpenTriplet => vec![self.tile; 3],
+            SetKind::Kan => vec![self.tile; 4],
+        }
+    }
+
+    /// 指定の牌を含むか
+    pub fn contains(&self, tile: &Tile) -> bool {
+        match self.kind {
+            SetKind::Sequence => (self.tile.index()..self.tile.index() + 3).contains(&tile.index()),
+            _ => self.tile.index() == tile.index(),
+        }
+    }
+
+    /// 刻子・槓子か
+    pub fn is_triplet(&self) -> bool {
+        self.kind != SetKind::Sequence
+    }
+}
+
+/// 和了牌の待ちの形
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitShape {
+    Ryanmen, // 両面
+    Kanchan, // 嵌張
+    Penchan, // 辺張
+    Shanpon, // 双碰
+    Tanki,   // 単騎
+}
+
+/// 和了形の分解（4面子1雀頭）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decomposition {
+    pub sets: Vec<HandSet>,
+    pub pair: Tile,
+    pub winning_tile: Tile,
+    pub winning_set: Option<usize>, // 和了牌で完成した面子の位置（Noneは雀頭で単騎）
+}
+
+impl Decomposition {
+    /// この分解における待ちの形
+    pub fn wait(&self) -> WaitShape {
+        let set = match self.winning_set {
+            Some(i) => self.sets[i],
+            None => return WaitShape::Tanki,
+        };
+
+        if set.is_triplet() {
+            return WaitShape::Shanpon;
+        }
+
+        let start = set.tile.index();
+        let winning = self.winning_tile.index();
+        if winning == start + 1 {
+            WaitShape::Kanchan
+        } else if (winning == start && start % 9 == 6) || (winning == start + 2 && start % 9 == 0) {
+            // 789の7、123の3で和了
+            WaitShape::Penchan
+        } else {
+            WaitShape::Ryanmen
+        }
+    }
+}
+
+/// 有効牌（受け入れ）の1種類分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ukeire {
+    pub tile: Tile,
+    pub remaining: usize, // 見えていない残り枚数
+}
+
+/// ツモ後の手牌で、打牌候補1種類ごとの有効牌
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscardUkeire {
+    pub discard: Tile,
+    pub shanten: i32, // 打牌後のシャンテン数
+    pub ukeire: Vec<Ukeire>,
 }
 
 impl Hand {
@@ -26,6 +142,7 @@ impl Hand {
         Self {
             tiles: Vec::new(),
             melds: Vec::new(),
+            drawn: None,
         }
     }
 
@@ -34,6 +151,36 @@ impl Hand {
         self.sort();
     }
 
+    /// ツモった牌を加える（打牌するまでツモ牌として区別する）
+    pub fn draw_tile(&mut self, tile: Tile) {
+        self.add_tile(tile);
+        self.drawn = Some(tile);
+    }
+
+    /// 直前にツモった牌
+    pub fn drawn_tile(&self) -> Option<Tile> {
+        self.drawn
+    }
+
+    /// ツモ牌を除いた手牌
+    pub fn held_tiles(&self) -> Vec<Tile> {
+        let mut tiles = self.tiles.clone();
+        if let Some(pos) = self.drawn.and_then(|drawn| tiles.iter().position(|t| *t == drawn)) {
+            tiles.remove(pos);
+        }
+        tiles
+    }
+
+    /// 打牌する（ツモ切りならtrue）
+    pub fn discard(&mut self, tile: &Tile) -> Option<bool> {
+        if !self.remove_tile(tile) {
+            return None;
+        }
+        let is_tsumogiri = self.drawn == Some(*tile);
+        self.drawn = None;
+        Some(is_tsumogiri)
+    }
+
     pub fn remove_tile(&mut self, tile: &Tile) -> bool {
         if let Some(pos) = self.tiles.iter().position(|t| t == tile) {
             self.tiles.remove(pos);
@@ -43,6 +190,17 @@ impl Hand {
         }
     }
 
+    /// 手牌から同じ種類の牌をcount枚選ぶ（赤ドラは後回し、足りなければNone）
+    pub fn tiles_of_kind(&self, tile: &Tile, count: usize) -> Option<Vec<Tile>> {
+        let mut same: Vec<Tile> = self.tiles.iter().filter(|t| t.index() == tile.index()).copied().collect();
+        if same.len() < count {
+            return None;
+        }
+        same.sort_by_key(|t| t.is_red);
+        same.truncate(count);
+        Some(same)
+    }
+
     pub fn get_tiles(&self) -> &Vec<Tile> {
         &self.tiles
     }
@@ -56,6 +214,23 @@ impl Hand {
             self.remove_tile(tile);
         }
         self.melds.push(meld);
+        self.drawn = None;
+    }
+
+    /// ポンした面子に4枚目を加えて加槓にする
+    pub fn upgrade_pon(&mut self, tile: &Tile) -> bool {
+        let index = self.melds.iter().position(|meld| {
+            meld.meld_type == MeldType::Pon && meld.tiles[0].normalized() == tile.normalized()
+        });
+        match index {
+            Some(index) if self.remove_tile(tile) => {
+                let meld = &mut self.melds[index];
+                meld.meld_type = MeldType::Kan(KanType::Added);
+                meld.tiles.push(*tile);
+                true
+            }
+            _ => false,
+        }
     }
 
     pub fn tile_count(&self) -> usize {
@@ -72,6 +247,94 @@ impl Hand {
         self.is_winning_hand(&test_tiles)
     }
 
+    /// 和了牌の一覧（ツモ前の13枚形のみ）
+    ///
+    /// 自分で4枚使っている牌は和了牌に含めない
+    pub fn waits(&self) -> Vec<Tile> {
+        if self.tile_count() % 3 != 1 {
+            return Vec::new();
+        }
+
+        let own_counts = self.own_tile_counts();
+        (0..TILE_KINDS)
+            .filter(|&index| own_counts[index] < 4)
+            .map(Tile::from_index)
+            .filter(|tile| self.can_win(tile))
+            .collect()
+    }
+
+    /// シャンテン数を下げる牌の一覧と残り枚数
+    ///
+    /// ツモ前の13枚形（3n+1枚）が対象で、ツモ後の14枚形は空を返す（打牌ごとは `discard_ukeire`）。
+    /// `visible` には自分の手牌・副露以外で見えている牌（河・他家の副露・ドラ表示牌）を渡す
+    pub fn ukeire(&self, visible: &[Tile]) -> Vec<Ukeire> {
+        if self.tile_count() % 3 != 1 {
+            return Vec::new();
+        }
+
+        let own_counts = self.own_tile_counts();
+        let visible_counts = Self::create_tile_counts(visible);
+        let current = self.shanten();
+
+        let mut result = Vec::new();
+        for index in 0..TILE_KINDS {
+            if own_counts[index] >= 4 {
+                continue;
+            }
+
+            let tile = Tile::from_index(index);
+            let mut test_hand = self.clone();
+            test_hand.tiles.push(tile);
+            if test_hand.shanten() < current {
+                let seen = own_counts[index] as usize + visible_counts[index] as usize;
+                result.push(Ukeire {
+                    tile,
+                    remaining: 4usize.saturating_sub(seen),
+                });
+            }
+        }
+        result
+    }
+
+    /// ツモ後の14枚形（3n+2枚）で、打牌候補ごとの打牌後シャンテン数と有効牌
+    ///
+    /// 打牌候補は牌種ごとに1つ（赤ドラは通常牌を優先）。捨てた牌は見えている牌として数える
+    pub fn discard_ukeire(&self, visible: &[Tile]) -> Vec<DiscardUkeire> {
+        if self.tile_count() % 3 != 2 {
+            return Vec::new();
+        }
+
+        let mut candidates: Vec<Tile> = self.tiles.clone();
+        candidates.sort_by_key(|tile| (tile.index(), tile.is_red));
+        candidates.dedup_by_key(|tile| tile.index());
+
+        candidates
+            .into_iter()
+            .map(|discard| {
+                let mut after = self.clone();
+                after.remove_tile(&discard);
+                let mut seen = visible.to_vec();
+                seen.push(discard);
+                DiscardUkeire {
+                    discard,
+                    shanten: after.shanten(),
+                    ukeire: after.ukeire(&seen),
+                }
+            })
+            .collect()
+    }
+
+    /// 手牌と副露を合わせた牌種ごとの枚数
+    fn own_tile_counts(&self) -> [u8; TILE_KINDS] {
+        let mut counts = Self::create_tile_counts(&self.tiles);
+        for meld in &self.melds {
+            for tile in &meld.tiles {
+                counts[tile.index()] += 1;
+            }
+        }
+        counts
+    }
+
     fn is_winning_hand(&self, tiles: &[Tile]) -> bool {
         // 手牌総数チェック
         let total_tiles = tiles.len() + self.melds.len() * 3;
@@ -98,6 +361,147 @@ impl Hand {
         self.check_normal_win(tiles)
     }
 
+    /// 和了形（4面子1雀頭）として取り得る全ての分解を列挙
+    ///
+    /// 和了牌がどの面子を完成させたかの違いも別の分解として返す。
+    /// ロン和了で完成した刻子は明刻として扱う
+    pub fn decompositions(&self, winning_tile: &Tile, is_tsumo: bool) -> Vec<Decomposition> {
+        let mut tiles = self.tiles.clone();
+        tiles.push(*winning_tile);
+        if tiles.len() + self.melds.len() * 3 != 14 {
+            return Vec::new();
+        }
+
+        let meld_sets: Vec<HandSet> = self.melds.iter().map(Self::meld_to_set).collect();
+        let melds_needed = 4 - self.melds.len();
+        let winning_index = winning_tile.index();
+        let mut counts = Self::create_tile_counts(&tiles);
+        let mut result = Vec::new();
+
+        for pair_index in 0..TILE_KINDS {
+            if counts[pair_index] < 2 {
+                continue;
+            }
+
+            counts[pair_index] -= 2;
+            let mut partitions = Vec::new();
+            Self::collect_partitions(&mut counts, 0, &mut Vec::new(), melds_needed, &mut partitions);
+            counts[pair_index] += 2;
+
+            let pair = Tile::from_index(pair_index);
+            for concealed in partitions {
+                let mut sets = concealed.clone();
+                sets.extend(meld_sets.iter().copied());
+
+                // 単騎待ち
+                if pair_index == winning_index {
+                    Self::push_unique(&mut result, Decomposition {
+                        sets: sets.clone(),
+                        pair,
+                        winning_tile: winning_tile.normalized(),
+                        winning_set: None,
+                    });
+                }
+
+                // 和了牌を含む面子
+                for (i, set) in concealed.iter().enumerate() {
+                    if !set.contains(winning_tile) {
+                        continue;
+                    }
+
+                    let mut sets = sets.clone();
+                    if set.kind == SetKind::ConcealedTriplet && !is_tsumo {
+                        sets[i].kind = SetKind::OpenTriplet;
+                    }
+                    Self::push_unique(&mut result, Decomposition {
+                        sets,
+                        pair,
+                        winning_tile: winning_tile.normalized(),
+                        winning_set: Some(i),
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    /// 残り牌を面子に分ける方法を全て集める（再帰）
+    fn collect_partitions(
+        counts: &mut [u8; TILE_KINDS],
+        start: usize,
+        current: &mut Vec<HandSet>,
+        melds_needed: usize,
+        result: &mut Vec<Vec<HandSet>>,
+    ) {
+        let index = match (start..TILE_KINDS).find(|&i| counts[i] > 0) {
+            Some(index) => index,
+            None => {
+                if current.len() == melds_needed {
+                    result.push(current.clone());
+                }
+                return;
+            }
+        };
+
+        // 刻子
+        if counts[index] >= 3 {
+            counts[index] -= 3;
+            current.push(HandSet {
+                kind: SetKind::ConcealedTriplet,
+                tile: Tile::from_index(index),
+                is_open: false,
+            });
+            Self::collect_partitions(counts, index, current, melds_needed, result);
+            current.pop();
+            counts[index] += 3;
+        }
+
+        // 順子（数牌のみ）
+        if index < 27 && index % 9 <= 6 && counts[index + 1] > 0 && counts[index + 2] > 0 {
+            counts[index] -= 1;
+            counts[index + 1] -= 1;
+            counts[index + 2] -= 1;
+            current.push(HandSet {
+                kind: SetKind::Sequence,
+                tile: Tile::from_index(index),
+                is_open: false,
+            });
+            Self::collect_partitions(counts, index, current, melds_needed, result);
+            current.pop();
+            counts[index] += 1;
+            counts[index + 1] += 1;
+            counts[index + 2] += 1;
+        }
+    }
+
+    /// 副露を和了形の面子に変換
+    fn meld_to_set(meld: &Meld) -> HandSet {
+        let tile = meld
+            .tiles
+            .iter()
+            .min_by_key(|t| t.index())
+            .map(|t| t.normalized())
+            .expect("meld must have tiles");
+        let kind = match meld.meld_type {
+            MeldType::Chi => SetKind::Sequence,
+            MeldType::Pon => SetKind::OpenTriplet,
+            MeldType::Kan(_) => SetKind::Kan,
+        };
+        HandSet {
+            kind,
+            tile,
+            is_open: meld.is_open,
+        }
+    }
+
+    /// 同一の分解を重複させずに追加
+    fn push_unique(result: &mut Vec<Decomposition>, decomposition: Decomposition) {
+        if !result.contains(&decomposition) {
+            result.push(decomposition);
+        }
+    }
+
     /// 通常形（4面子1雀頭）の判定
     fn check_normal_win(&self, tiles: &[Tile]) -> bool {
         let mut tile_map = Self::create_tile_map(tiles);
@@ -223,7 +627,7 @@ impl Hand {
 
     /// 国士無双の判定
     fn check_kokushi(&self, tiles: &[Tile]) -> bool {
-        use crate::tile::{TileType, Honor, Suit};
+        use crate::tile::{Honor, Suit};
 
         if tiles.len() != 14 {
             return false;
@@ -274,134 +678,156 @@ impl Hand {
     }
 
     /// シャンテン数の計算
+    ///
+    /// 和了形は-1、テンパイは0を返す
     pub fn shanten(&self) -> i32 {
+        let normal = self.shanten_normal(&self.tiles);
         if self.melds.is_empty() {
             // 七対子・国士無双も考慮して最小値を返す
-            let normal = self.shanten_normal(&self.tiles);
             let chitoi = self.shanten_chitoi(&self.tiles);
             let kokushi = self.shanten_kokushi(&self.tiles);
 
             normal.min(chitoi).min(kokushi)
         } else {
             // 副露がある場合は通常形のみ
-            self.shanten_normal(&self.tiles)
+            normal
         }
     }
 
     /// 通常形のシャンテン数
+    ///
+    /// 雀頭の候補ごとに面子・塔子の取り方を全探索し、
+    /// `2 * 必要面子数 - 2 * 面子数 - 塔子数 - 雀頭` の最小値を求める
     fn shanten_normal(&self, tiles: &[Tile]) -> i32 {
-        let tile_map = Self::create_tile_map(tiles);
-        let melds_needed = 4 - self.melds.len();
-
-        // 簡易実装：完全なシャンテン計算は複雑なため、暫定版
-        // TODO: より正確な実装
-        let mut min_shanten = 8;
-
-        for tile in tiles {
-            if tile_map.get(tile).copied().unwrap_or(0) >= 2 {
-                let mut test_map = tile_map.clone();
-                *test_map.get_mut(tile).unwrap() -= 2;
-
-                let melds_made = self.count_melds(&test_map);
-                let shanten = melds_needed as i32 - melds_made as i32 - 1;
-                min_shanten = min_shanten.min(shanten.max(0));
+        let mut counts = Self::create_tile_counts(tiles);
+        let melds_needed = 4usize.saturating_sub(self.melds.len());
+        let base = 2 * melds_needed as i32;
+
+        // 雀頭なし
+        let mut min_shanten = base - Self::best_block_score(&mut counts, 0, 0, 0, melds_needed);
+
+        // 雀頭あり
+        for index in 0..TILE_KINDS {
+            if counts[index] >= 2 {
+                counts[index] -= 2;
+                let shanten = base - 1 - Self::best_block_score(&mut counts, 0, 0, 0, melds_needed);
+                counts[index] += 2;
+                min_shanten = min_shanten.min(shanten);
             }
         }
 
         min_shanten
     }
 
-    /// 七対子形のシャンテン数
-    fn shanten_chitoi(&self, tiles: &[Tile]) -> i32 {
-        if tiles.len() != 13 {
-            return 8;
+    /// 面子・塔子の取り方を探索し、`2 * 面子数 + 塔子数` の最大値を返す（再帰）
+    ///
+    /// 面子と塔子の合計は必要面子数までしか数えない
+    fn best_block_score(
+        counts: &mut [u8; TILE_KINDS],
+        start: usize,
+        melds: usize,
+        taatsu: usize,
+        melds_needed: usize,
+    ) -> i32 {
+        let index = match (start..TILE_KINDS).find(|&i| counts[i] > 0) {
+            Some(index) => index,
+            None => {
+                let melds = melds.min(melds_needed);
+                let taatsu = taatsu.min(melds_needed - melds);
+                return (2 * melds + taatsu) as i32;
+            }
+        };
+
+        // 数牌の場合は同じ色の中での位置（0-8）
+        let position = if index < 27 { Some(index % 9) } else { None };
+        let mut best = 0;
+
+        // 刻子
+        if counts[index] >= 3 {
+            counts[index] -= 3;
+            best = best.max(Self::best_block_score(counts, index, melds + 1, taatsu, melds_needed));
+            counts[index] += 3;
         }
 
-        let tile_map = Self::create_tile_map(tiles);
-        let pairs = tile_map.iter().filter(|(_, &count)| count >= 2).count();
+        // 順子
+        if matches!(position, Some(p) if p <= 6) && counts[index + 1] > 0 && counts[index + 2] > 0 {
+            counts[index] -= 1;
+            counts[index + 1] -= 1;
+            counts[index + 2] -= 1;
+            best = best.max(Self::best_block_score(counts, index, melds + 1, taatsu, melds_needed));
+            counts[index] += 1;
+            counts[index + 1] += 1;
+            counts[index + 2] += 1;
+        }
 
-        6 - pairs as i32
-    }
+        // 対子（塔子として）
+        if counts[index] >= 2 {
+            counts[index] -= 2;
+            best = best.max(Self::best_block_score(counts, index, melds, taatsu + 1, melds_needed));
+            counts[index] += 2;
+        }
 
-    /// 国士無双形のシャンテン数
-    fn shanten_kokushi(&self, tiles: &[Tile]) -> i32 {
-        use crate::tile::{TileType, Honor, Suit};
+        // 両面・辺張
+        if matches!(position, Some(p) if p <= 7) && counts[index + 1] > 0 {
+            counts[index] -= 1;
+            counts[index + 1] -= 1;
+            best = best.max(Self::best_block_score(counts, index, melds, taatsu + 1, melds_needed));
+            counts[index] += 1;
+            counts[index + 1] += 1;
+        }
 
-        let tile_map = Self::create_tile_map(tiles);
+        // 嵌張
+        if matches!(position, Some(p) if p <= 6) && counts[index + 2] > 0 {
+            counts[index] -= 1;
+            counts[index + 2] -= 1;
+            best = best.max(Self::best_block_score(counts, index, melds, taatsu + 1, melds_needed));
+            counts[index] += 1;
+            counts[index + 2] += 1;
+        }
 
-        let terminals_and_honors = vec![
-            Tile::new_number(Suit::Man, 1, false),
-            Tile::new_number(Suit::Man, 9, false),
-            Tile::new_number(Suit::Pin, 1, false),
-            Tile::new_number(Suit::Pin, 9, false),
-            Tile::new_number(Suit::Sou, 1, false),
-            Tile::new_number(Suit::Sou, 9, false),
-            Tile::new_honor(Honor::Ton),
-            Tile::new_honor(Honor::Nan),
-            Tile::new_honor(Honor::Shaa),
-            Tile::new_honor(Honor::Pei),
-            Tile::new_honor(Honor::Haku),
-            Tile::new_honor(Honor::Hatsu),
-            Tile::new_honor(Honor::Chun),
-        ];
+        // 残りを孤立牌として扱う
+        let count = counts[index];
+        counts[index] = 0;
+        best = best.max(Self::best_block_score(counts, index + 1, melds, taatsu, melds_needed));
+        counts[index] = count;
 
-        let mut kinds = 0;
-        let mut has_pair = false;
+        best
+    }
 
-        for yaochu in &terminals_and_honors {
-            if let Some(&count) = tile_map.get(yaochu) {
-                if count > 0 {
-                    kinds += 1;
-                    if count >= 2 {
-                        has_pair = true;
-                    }
-                }
-            }
+    /// 七対子形のシャンテン数
+    fn shanten_chitoi(&self, tiles: &[Tile]) -> i32 {
+        if tiles.len() < 13 {
+            return 8;
         }
 
-        let mut shanten = 13 - kinds;
-        if !has_pair {
-            shanten -= 1;
-        }
+        let counts = Self::create_tile_counts(tiles);
+        let pairs = counts.iter().filter(|&&count| count >= 2).count() as i32;
+        let kinds = counts.iter().filter(|&&count| count >= 1).count() as i32;
 
-        shanten
+        // 同じ牌の4枚使いは2対子と数えないため、種類不足分を加算
+        6 - pairs + (7 - kinds).max(0)
     }
 
-    /// 面子の数を数える（簡易版）
-    fn count_melds(&self, tile_map: &HashMap<Tile, usize>) -> usize {
-        let mut count = 0;
-        let mut map = tile_map.clone();
-
-        // 刻子を優先的に数える
-        for (tile, &tile_count) in tile_map.iter() {
-            if tile_count >= 3 {
-                let sets = tile_count / 3;
-                count += sets;
-                *map.get_mut(tile).unwrap() -= sets * 3;
-            }
+    /// 国士無双形のシャンテン数
+    fn shanten_kokushi(&self, tiles: &[Tile]) -> i32 {
+        if tiles.len() < 13 {
+            return 13;
         }
 
-        // 順子を数える（簡易版）
-        for (tile, &tile_count) in map.iter() {
-            if tile_count > 0 {
-                if let crate::tile::TileType::Number { suit, value } = tile.tile_type {
-                    if value <= 7 {
-                        let tile2 = crate::tile::Tile::new_number(suit, value + 1, false);
-                        let tile3 = crate::tile::Tile::new_number(suit, value + 2, false);
+        let counts = Self::create_tile_counts(tiles);
+        let kinds = YAOCHU_INDICES.iter().filter(|&&i| counts[i] >= 1).count() as i32;
+        let has_pair = YAOCHU_INDICES.iter().any(|&i| counts[i] >= 2);
 
-                        let min = tile_count
-                            .min(map.get(&tile2).copied().unwrap_or(0))
-                            .min(map.get(&tile3).copied().unwrap_or(0));
+        13 - kinds - if has_pair { 1 } else { 0 }
+    }
 
-                        if min > 0 {
-                            count += min;
-                        }
-                    }
-                }
-            }
+    /// 牌種ごとの枚数配列を作成（赤ドラは通常牌として扱う）
+    fn create_tile_counts(tiles: &[Tile]) -> [u8; TILE_KINDS] {
+        let mut counts = [0; TILE_KINDS];
+        for tile in tiles {
+            counts[tile.index()] += 1;
         }
-
-        count
+        counts
     }
 
     /// 牌のカウントマップを作成
@@ -409,17 +835,7 @@ impl Hand {
         let mut map = HashMap::new();
         for tile in tiles {
             // 赤ドラは通常牌として扱う
-            let normalized = if tile.is_red {
-                match tile.tile_type {
-                    crate::tile::TileType::Number { suit, value } => {
-                        crate::tile::Tile::new_number(suit, value, false)
-                    }
-                    _ => *tile,
-                }
-            } else {
-                *tile
-            };
-            *map.entry(normalized).or_insert(0) += 1;
+            *map.entry(tile.normalized()).or_insert(0) += 1;
         }
         map
     }
@@ -454,15 +870,26 @@ impl Hand {
             }
         });
     }
+}
+
+impl Default for Hand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    pub fn to_string(&self) -> String {
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut result = String::new();
-        
-        // 手牌
-        for tile in &self.tiles {
+
+        // 手牌（ツモ牌は右端に離して表示）
+        for tile in self.held_tiles() {
             result.push_str(&tile.to_string());
             result.push(' ');
         }
+        if let Some(drawn) = self.drawn {
+            result.push_str(&format!(" {} ", drawn));
+        }
 
         // 副露
         if !self.melds.is_empty() {
@@ -475,13 +902,7 @@ impl Hand {
             }
         }
 
-        result.trim().to_string()
-    }
-}
-
-impl Default for Hand {
-    fn default() -> Self {
-        Self::new()
+        write!(f, "{}", result.trim())
     }
 }
 
@@ -490,6 +911,14 @@ mod tests {
     use super::*;
     use crate::tile::{Tile, Suit, Honor};
 
+    fn hand_from(s: &str) -> Hand {
+        let mut hand = Hand::new();
+        for tile in Tile::parse_tiles(s).unwrap() {
+            hand.add_tile(tile);
+        }
+        hand
+    }
+
     #[test]
     fn test_hand_operations() {
         let mut hand = Hand::new();
@@ -517,4 +946,243 @@ mod tests {
         assert_eq!(tiles[2].to_string(), "5p");
         assert_eq!(tiles[3].to_string(), "to");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_shanten_table() {
+        let cases = [
+            // テンパイ
+            ("123m 456p 789s to to to hk", 0),
+            ("123m 456p 789s to to hk hk", 0),
+            ("123456789m 1234p", 0),
+            ("11123m 456p 789s to to", 0),
+            ("1223334m 456p 789s", 0),
+            ("2345678m 222p 567s", 0),
+            ("1112345678999m", 0),
+            ("11m 22m 33p 44p 55s 66s to", 0),
+            ("19m 19p 19s to na sa pe hk ht cn", 0),
+            ("19m 19p 19s to na sa pe hk ht ht", 0),
+            // 一向聴
+            ("123m 456p 789s 13s hk ht", 1),
+            ("123m 456p 78s 13s hk hk", 1),
+            ("11m 22m 33p 44p 55s 6s to na", 1),
+            ("2233m 4455p 6677s to", 0),
+            // 二向聴以上
+            ("123m 456p 13s 57s hk ht cn", 2),
+            ("1111m 22m 33p 44p 55s 6s", 2),
+            ("123m 57m 26p 13s 99s hk ht", 3),
+            ("159m 26p 48s to na sa hk ht cn", 5),
+            ("147m 258p 369s to na sa pe", 6),
+            // 和了形
+            ("123m 456p 789s to to to hk hk", -1),
+            ("11m 22m 33p 44p 55s 66s to to", -1),
+            ("19m 19p 19s to na sa pe hk ht cn cn", -1),
+        ];
+
+        for (tiles, expected) in cases {
+            assert_eq!(hand_from(tiles).shanten(), expected, "{}", tiles);
+        }
+    }
+
+    #[test]
+    fn test_shanten_with_melds() {
+        use crate::tile::Honor;
+
+        let mut hand = hand_from("123m 456p 78s 99s to to to");
+        let ton = Tile::new_honor(Honor::Ton);
+        hand.add_meld(Meld {
+            meld_type: MeldType::Pon,
+            tiles: vec![ton, ton, ton],
+            is_open: true,
+            from: Some(3),
+            called_tile: Some(ton),
+        });
+        assert_eq!(hand.shanten(), 0);
+
+        hand.add_tile(Tile::new_number(Suit::Sou, 9, false));
+        assert_eq!(hand.shanten(), -1);
+    }
+
+    #[test]
+    fn test_waits() {
+        let hand = hand_from("123m 456p 789s 13s to to");
+        let waits: Vec<String> = hand.waits().iter().map(|t| t.to_string()).collect();
+        assert_eq!(waits, vec!["2s"]);
+
+        // 純正九蓮宝燈は九面待ち
+        let hand = hand_from("1112345678999m");
+        assert_eq!(hand.waits().len(), 9);
+
+        // 国士無双十三面待ち
+        let hand = hand_from("19m 19p 19s to na sa pe hk ht cn");
+        assert_eq!(hand.waits().len(), 13);
+
+        // ノーテンなら空
+        let hand = hand_from("147m 258p 369s to na sa pe");
+        assert!(hand.waits().is_empty());
+    }
+
+    #[test]
+    fn test_ukeire() {
+        // 36p両面待ち
+        let hand = hand_from("123m 456m 789s 45p to to");
+        let ukeire = hand.ukeire(&[]);
+        let tiles: Vec<String> = ukeire.iter().map(|u| u.tile.to_string()).collect();
+        assert_eq!(tiles, vec!["3p", "6p"]);
+        assert!(ukeire.iter().all(|u| u.remaining == 4));
+
+        // 見えている牌を差し引く
+        let visible = Tile::parse_tiles("3p 3p 6p").unwrap();
+        let ukeire = hand.ukeire(&visible);
+        let remaining: usize = ukeire.iter().map(|u| u.remaining).sum();
+        assert_eq!(remaining, 5);
+
+        // 一向聴の受け入れ
+        let hand = hand_from("123m 456p 789s 13s hk ht");
+        let tiles: Vec<String> = hand.ukeire(&[]).iter().map(|u| u.tile.to_string()).collect();
+        assert_eq!(tiles, vec!["2s", "hk", "ht"]);
+
+        // 14枚形は ukeire では扱わない
+        let hand = hand_from("123m 456m 789s 45p to to 9m");
+        assert!(hand.ukeire(&[]).is_empty());
+        assert!(hand_from("123m 456m 789s 45p to to").discard_ukeire(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_discard_ukeire() {
+        let hand = hand_from("123m 456m 789s 45p to to 9m");
+        let options = hand.discard_ukeire(&[]);
+        assert_eq!(options.len(), 13); // 牌種ごとに1つ
+
+        // 9m切りでテンパイ、36p待ち
+        let best = options.iter().find(|o| o.discard.to_string() == "9m").unwrap();
+        assert_eq!(best.shanten, 0);
+        let tiles: Vec<String> = best.ukeire.iter().map(|u| u.tile.to_string()).collect();
+        assert_eq!(tiles, vec!["3p", "6p"]);
+
+        // 捨てた牌は見えている牌として残り枚数から除く
+        let hand = hand_from("123m 456m 789s 45p to to 6p");
+        let six_pin = hand.discard_ukeire(&[]).into_iter().find(|o| o.discard.to_string() == "6p").unwrap();
+        assert_eq!(six_pin.ukeire.iter().find(|u| u.tile.to_string() == "6p").unwrap().remaining, 3);
+    }
+
+    #[test]
+    fn test_decompositions() {
+        // 111222333m は順子3組と刻子3組の2通り
+        let hand = hand_from("111222333m 456p 9s");
+        let winning_tile = Tile::new_number(Suit::Sou, 9, false);
+        let decompositions = hand.decompositions(&winning_tile, true);
+        assert_eq!(decompositions.len(), 2);
+        assert!(decompositions.iter().all(|d| d.winning_set.is_none()));
+        assert!(decompositions.iter().any(|d| d
+            .sets
+            .iter()
+            .filter(|s| s.kind == SetKind::ConcealedTriplet)
+            .count()
+            == 3));
+
+        // 和了牌の入り方（シャンポン）でロンなら明刻になる
+        let hand = hand_from("123m 456p 789s 55s hk hk");
+        let winning_tile = Tile::new_honor(Honor::Haku);
+        let decompositions = hand.decompositions(&winning_tile, false);
+        assert_eq!(decompositions.len(), 1);
+        let decomposition = &decompositions[0];
+        let winning_set = decomposition.sets[decomposition.winning_set.unwrap()];
+        assert_eq!(winning_set.kind, SetKind::OpenTriplet);
+        assert_eq!(decomposition.pair.to_string(), "5s");
+
+        // 和了牌が複数の面子に入り得る場合はそれぞれ列挙
+        let hand = hand_from("23456m 456p 789s to to");
+        let winning_tile = Tile::new_number(Suit::Man, 4, false);
+        let decompositions = hand.decompositions(&winning_tile, true);
+        assert_eq!(decompositions.len(), 2);
+        let winning_tile = Tile::new_number(Suit::Man, 7, false);
+        let decompositions = hand.decompositions(&winning_tile, true);
+        assert_eq!(decompositions.len(), 1);
+
+        // 和了形でなければ空
+        let hand = hand_from("147m 258p 369s to na sa pe");
+        assert!(hand.decompositions(&winning_tile, true).is_empty());
+    }
+
+    #[test]
+    fn test_wait_shapes() {
+        let cases = [
+            ("123m 456p 789s 34s to to", "2s", WaitShape::Ryanmen),
+            ("123m 456p 789s 35s to to", "4s", WaitShape::Kanchan),
+            ("123m 456p 789s 12s to to", "3s", WaitShape::Penchan),
+            ("123m 456p 789s 89m to to", "7m", WaitShape::Penchan),
+            ("123m 456p 789s 55s to to", "to", WaitShape::Shanpon),
+            ("123m 456p 789s 555s to", "to", WaitShape::Tanki),
+        ];
+
+        for (tiles, winning, expected) in cases {
+            let winning_tile = Tile::from_string(winning).unwrap();
+            let decompositions = hand_from(tiles).decompositions(&winning_tile, false);
+            assert!(decompositions.iter().any(|d| d.wait() == expected), "{} + {}", tiles, winning);
+        }
+    }
+
+    #[test]
+    fn test_decompositions_with_melds() {
+        let mut hand = hand_from("123m 456p 78s 99s hk hk hk");
+        let haku = Tile::new_honor(Honor::Haku);
+        hand.add_meld(Meld {
+            meld_type: MeldType::Pon,
+            tiles: vec![haku, haku, haku],
+            is_open: true,
+            from: Some(3),
+            called_tile: Some(haku),
+        });
+
+        // 78s両面と9s単騎の2通り
+        let winning_tile = Tile::new_number(Suit::Sou, 9, false);
+        let decompositions = hand.decompositions(&winning_tile, false);
+        assert_eq!(decompositions.len(), 2);
+        for decomposition in &decompositions {
+            assert_eq!(decomposition.sets.len(), 4);
+            assert!(decomposition.sets.iter().any(|s| s.kind == SetKind::OpenTriplet && s.is_open));
+        }
+        assert!(decompositions.iter().any(|d| d.winning_set.is_none()));
+        assert!(decompositions
+            .iter()
+            .filter_map(|d| d.winning_set.map(|i| d.sets[i]))
+            .any(|s| s.tile.to_string() == "7s"));
+    }
+
+    #[test]
+    fn test_upgrade_pon() {
+        let mut hand = hand_from("5m 5m 5m 5mr 19p");
+        let five = Tile::new_number(Suit::Man, 5, false);
+        hand.add_meld(Meld {
+            meld_type: MeldType::Pon,
+            tiles: vec![five, five, five],
+            is_open: true,
+            from: Some(3),
+            called_tile: Some(five),
+        });
+
+        // 赤5でも同じ牌として加槓できる
+        let red_five = Tile::new_number(Suit::Man, 5, true);
+        assert!(!hand.upgrade_pon(&Tile::new_number(Suit::Pin, 1, false)));
+        assert!(hand.upgrade_pon(&red_five));
+        assert_eq!(hand.get_melds()[0].meld_type, MeldType::Kan(KanType::Added));
+        assert_eq!(hand.get_melds()[0].tiles.len(), 4);
+        assert_eq!(hand.get_tiles().len(), 2);
+    }
+
+    #[test]
+    fn test_drawn_tile() {
+        let mut hand = hand_from("23m 456p 789s to to to hk hk");
+        let one_man = Tile::new_number(Suit::Man, 1, false);
+        hand.draw_tile(one_man);
+
+        // 理牌しても先頭に並んだツモ牌を区別できる
+        assert_eq!(hand.get_tiles()[0], one_man);
+        assert_eq!(hand.drawn_tile(), Some(one_man));
+        assert!(!hand.held_tiles().contains(&one_man));
+
+        assert_eq!(hand.discard(&Tile::new_honor(Honor::Haku)), Some(false));
+        assert_eq!(hand.drawn_tile(), None);
+        assert_eq!(hand.held_tiles().len(), 13);
+    }
+}
@@ -1,8 +1,14 @@
 pub mod tile;
+pub mod wall;
 pub mod hand;
 pub mod game;
+pub mod action;
+pub mod game_log;
 pub mod player;
 pub mod scoring;
+pub mod rules;
+pub mod standings;
+pub mod rng;
 pub mod ai;
 pub mod nostr;
 
@@ -16,8 +22,14 @@ pub mod wasm_nostr;
 pub mod wasm_webrtc;
 
 pub use tile::{Tile, TileType, Suit};
-pub use hand::Hand;
-pub use game::Game;
+pub use wall::Wall;
+pub use hand::{DiscardUkeire, Hand, KanType, MeldType};
+pub use game::{Game, Claim, HandResult, WinResult, DrawResult, AbortReason};
+pub use action::{Action, Event, Phase, RuleError};
+pub use game_log::{GameLog, LOG_VERSION};
 pub use player::Player;
+pub use rules::{RuleSet, MultipleRon, GameLength, AbortiveDraws, KanDoraTiming, SanmaTsumo};
+pub use standings::{MatchResult, Standing};
 pub use ai::{AiEngine, AiLevel};
+pub use rng::GameRng;
 pub use nostr::{NostrClient, NostrKeys, GameEvent, GameEventType, MatchState};
\ No newline at end of file
@@ -1,36 +1,72 @@
-use xmj_core::{Game, Tile, AiEngine, AiLevel};
+use xmj_core::{Action, Game, Claim, Phase, Tile, AiEngine, AiLevel, RuleSet};
 use std::io::{self, Write};
 
 fn main() {
     println!("邪雀 Xtreme Mahjong (xmj) - CUIクライアント");
     println!("==========================================");
     
-    let player_names = vec![
+    // --sanma で三人麻雀
+    let rules = if std::env::args().any(|arg| arg == "--sanma") {
+        RuleSet::sanma()
+    } else {
+        RuleSet::default()
+    };
+
+    let mut player_names = vec![
         "あなた".to_string(),
         "CPU1".to_string(), 
         "CPU2".to_string(),
         "CPU3".to_string(),
     ];
+    player_names.truncate(rules.players);
     
-    let mut game = Game::new(player_names);
+    // --seed <数値> で山とCPUの選択を再現
+    let args: Vec<String> = std::env::args().collect();
+    let mut game = match args.iter().position(|arg| arg == "--seed").and_then(|i| args.get(i + 1)) {
+        Some(seed) => Game::with_seed(player_names, rules, seed.parse().expect("シード値は数値で指定してください")),
+        None => Game::new(player_names, rules),
+    };
+    println!("シード値: {}", game.seed());
+
+    // CPUごとのAI（シード値から決まる）
+    let mut ais: Vec<AiEngine> = (0..game.player_count())
+        .map(|i| AiEngine::with_seed(AiLevel::Intermediate, game.ai_seed(i)))
+        .collect();
     
     println!("{}", game.get_game_state_string());
     
     loop {
         if game.is_game_over() {
+            print!("{}", game.get_hand_result_string());
             println!("ゲーム終了");
+            if let Some(result) = game.match_result() {
+                for standing in &result.standings {
+                    println!("{}位 {}: {}点 ({:+.1})", standing.rank, standing.name, standing.score, standing.points);
+                }
+            }
+            save_log(&game, &args);
             break;
         }
+
+        if game.phase() == Phase::HandOver {
+            print!("{}", game.get_hand_result_string());
+            if game.next_hand().is_ok() {
+                println!("\n{}", game.get_round_string());
+                println!("{}", game.get_game_state_string());
+            }
+            continue;
+        }
         
         let current_player = game.get_current_player();
+        let current_id = current_player.id;
         println!("\n{} のターン:", current_player.name);
         
-        if current_player.id == 0 {
+        if current_id == 0 {
             // プレイヤーのターン
             handle_player_turn(&mut game);
         } else {
             // CPUのターン
-            handle_cpu_turn(&mut game);
+            handle_cpu_turn(&mut game, &mut ais[current_id]);
         }
         
         println!("{}", game.get_game_state_string());
@@ -38,30 +74,36 @@ fn main() {
 }
 
 fn handle_player_turn(game: &mut Game) {
-    // ツモ
-    if !game.current_player_draw() {
+    // ツモ（鳴いた直後や配牌直後の親はツモらない）
+    if game.phase() == Phase::AwaitingDraw && !game.current_player_draw() {
         println!("山牌がありません");
         return;
     }
     
+    // 北抜き（三人麻雀）
+    while game.can_kita(0) && ask_yes_no("北を抜きますか？ (y/n): ") {
+        game.declare_kita(0);
+    }
+
     let player = game.get_current_player();
     println!("ツモ: 手牌 {}", player.get_hand_string());
     
     // 和了チェック
     if player.tile_count() == 14 {
-        // 簡単な和了チェック（完全ではない）
-        if player.is_tenpai() {
-            print!("ツモ和了しますか？ (y/n): ");
-            io::stdout().flush().unwrap();
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            
-            if input.trim().to_lowercase() == "y" {
-                println!("ツモ！");
-                return;
-            }
+        // 和了形かつ役あり
+        if game.can_tsumo(0) && ask_yes_no("ツモ和了しますか？ (y/n): ") {
+            println!("ツモ！");
+            game.declare_tsumo(0);
+            return;
         }
     }
+
+    // 九種九牌
+    if game.can_kyuushu_kyuuhai(0) && ask_yes_no("九種九牌で流局しますか？ (y/n): ") {
+        println!("九種九牌");
+        game.declare_kyuushu_kyuuhai(0);
+        return;
+    }
     
     // 打牌選択
     loop {
@@ -73,11 +115,14 @@ fn handle_player_turn(game: &mut Game) {
         let input = input.trim();
         
         if let Some(tile) = Tile::from_string(input) {
-            if game.discard_tile(tile) {
-                println!("打牌: {}", tile.to_string());
-                break;
-            } else {
-                println!("その牌は手牌にありません");
+            match game.apply(Action::Discard { player: 0, tile }) {
+                Ok(_) => {
+                    println!("打牌: {}", tile);
+                    print_waits(game, 0);
+                    handle_claims(game);
+                    break;
+                }
+                Err(err) => println!("{}", err),
             }
         } else {
             println!("無効な牌です");
@@ -85,25 +130,133 @@ fn handle_player_turn(game: &mut Game) {
     }
 }
 
-fn handle_cpu_turn(game: &mut Game) {
+fn handle_cpu_turn(game: &mut Game, ai: &mut AiEngine) {
     let player_name = game.get_current_player().name.clone();
 
     // ツモ
-    if !game.current_player_draw() {
+    if game.phase() == Phase::AwaitingDraw && !game.current_player_draw() {
         println!("山牌がありません");
         return;
     }
 
+    // CPUは北があれば必ず抜く
+    let current_idx = game.current_player();
+    while game.can_kita(current_idx) && !game.can_tsumo(current_idx) {
+        println!("{} が北抜き", player_name);
+        game.declare_kita(current_idx);
+    }
+
+    // CPUは和了できれば必ずツモ和了
+    if game.can_tsumo(current_idx) {
+        println!("{} がツモ和了", player_name);
+        game.declare_tsumo(current_idx);
+        return;
+    }
+
     // AIエンジンで打牌を選択（レベル3: シャンテン数ベース）
-    let ai = AiEngine::new(AiLevel::Intermediate);
     let hand = &game.get_current_player().hand;
 
-    if let Some(discard_tile) = ai.select_discard(hand) {
+    let legal = game.legal_discards(current_idx);
+
+    if let Some(discard_tile) = ai.select_legal_discard(hand, &legal) {
         game.discard_tile(discard_tile);
         println!("{} が {} を打牌 [シャンテン数: {}]",
             player_name,
-            discard_tile.to_string(),
-            game.get_current_player().hand.shanten()
+            discard_tile,
+            game.player(current_idx).hand.shanten()
         );
+        handle_claims(game);
+    }
+}
+
+/// 打牌に対する鳴き・ロンの受付（CPUはロンできればロン、それ以外はパス）
+fn handle_claims(game: &mut Game) {
+    while let Some(window) = game.claim_window().cloned() {
+        let player_idx = window.pending[0];
+        let claims = game.available_claims(player_idx);
+
+        let claim = if player_idx == 0 {
+            ask_claim(&claims)
+        } else if claims.contains(&Claim::Ron) {
+            Claim::Ron
+        } else {
+            Claim::Pass
+        };
+
+        if claim != Claim::Pass {
+            println!("{}: {}", game.player(player_idx).name, claim_label(claim));
+        }
+        game.respond(player_idx, claim);
+    }
+}
+
+fn ask_claim(claims: &[Claim]) -> Claim {
+    println!("0: パス");
+    for (i, claim) in claims.iter().enumerate() {
+        println!("{}: {}", i + 1, claim_label(*claim));
     }
+
+    loop {
+        print!("選択してください: ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().parse::<usize>() {
+            Ok(0) => return Claim::Pass,
+            Ok(n) if n <= claims.len() => return claims[n - 1],
+            _ => println!("無効な選択です"),
+        }
+    }
+}
+
+fn claim_label(claim: Claim) -> &'static str {
+    match claim {
+        Claim::Pass => "パス",
+        Claim::Chi(0) => "チー（下の2枚）",
+        Claim::Chi(1) => "チー（嵌張）",
+        Claim::Chi(_) => "チー（上の2枚）",
+        Claim::Pon => "ポン",
+        Claim::Kan => "カン",
+        Claim::Ron => "ロン",
+    }
+}
+
+fn ask_yes_no(prompt: &str) -> bool {
+    print!("{}", prompt);
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    input.trim().to_lowercase() == "y"
+}
+
+/// --log <ファイル> が指定されていれば牌譜をJSONで保存
+fn save_log(game: &Game, args: &[String]) {
+    let Some(path) = args.iter().position(|arg| arg == "--log").and_then(|i| args.get(i + 1)) else {
+        return;
+    };
+    let saved = game
+        .log()
+        .to_json()
+        .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+    match saved {
+        Ok(()) => println!("牌譜を保存しました: {}", path),
+        Err(err) => println!("牌譜を保存できません: {}", err),
+    }
+}
+
+/// テンパイしていれば待ちと残り枚数を表示
+fn print_waits(game: &Game, player_idx: usize) {
+    if !game.player(player_idx).is_tenpai() {
+        return;
+    }
+
+    let ukeire = game.get_ukeire(player_idx);
+    let waits = ukeire
+        .iter()
+        .map(|u| u.tile.to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    let remaining: usize = ukeire.iter().map(|u| u.remaining).sum();
+    let furiten = if game.player(player_idx).is_furiten() { " [フリテン]" } else { "" };
+    println!("待ち: {}（残り{}枚）{}", waits, remaining, furiten);
 }
@@ -1,3 +1,4 @@
+use crate::standings::MatchResult;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -121,6 +122,32 @@ impl GameEvent {
         self
     }
 
+    /// シード値を載せたゲーム開始イベント（同じ山を再現できる）
+    pub fn game_start(game_id: String, player_id: String, seed: u64) -> Self {
+        Self::new(GameEventType::GameStart, game_id, player_id).with_data("seed".to_string(), seed.to_string())
+    }
+
+    /// ゲーム開始イベントからシード値を取り出す
+    pub fn seed(&self) -> Option<u64> {
+        if self.event_type != GameEventType::GameStart {
+            return None;
+        }
+        self.data.get("seed")?.parse().ok()
+    }
+
+    /// 終局結果を載せたゲーム終了イベント
+    pub fn game_end(game_id: String, player_id: String, result: &MatchResult) -> Result<Self, String> {
+        Ok(Self::new(GameEventType::GameEnd, game_id, player_id).with_data("result".to_string(), result.to_json()?))
+    }
+
+    /// ゲーム終了イベントから終局結果を取り出す
+    pub fn match_result(&self) -> Option<MatchResult> {
+        if self.event_type != GameEventType::GameEnd {
+            return None;
+        }
+        MatchResult::from_json(self.data.get("result")?).ok()
+    }
+
     pub fn to_json(&self) -> Result<String, String> {
         serde_json::to_string(self).map_err(|e| e.to_string())
     }
@@ -267,7 +294,6 @@ impl NostrClient {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
 
     #[test]
     fn test_keys_generation() {
@@ -315,6 +341,26 @@ mod tests {
         assert_eq!(parsed.game_id, "game123");
     }
 
+    #[test]
+    fn test_game_end_carries_match_result() {
+        let players: Vec<crate::player::Player> = (0..4)
+            .map(|i| crate::player::Player::new(i, format!("P{}", i + 1)))
+            .collect();
+        let result = MatchResult::new(&players, 1, &crate::rules::RuleSet::default());
+
+        let event = GameEvent::game_end("game123".to_string(), "player1".to_string(), &result).unwrap();
+        let parsed = GameEvent::from_json(&event.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.match_result(), Some(result));
+    }
+
+    #[test]
+    fn test_game_start_carries_seed() {
+        let event = GameEvent::game_start("game123".to_string(), "player1".to_string(), u64::MAX);
+        let parsed = GameEvent::from_json(&event.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.seed(), Some(u64::MAX));
+        assert_eq!(GameEvent::new(GameEventType::DrawTile, "game123".to_string(), "player1".to_string()).seed(), None);
+    }
+
     #[test]
     fn test_match_state() {
         let mut match_state = MatchState::new(
@@ -1,5 +1,21 @@
 use crate::hand::Hand;
-use crate::tile::Tile;
+use crate::tile::{Tile, TileType};
+
+/// 河の1枚
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Discard {
+    pub tile: Tile,
+    pub called_by: Option<usize>, // 鳴いたプレイヤー（牌は副露側に移っている）
+    pub tsumogiri: bool,          // ツモ切り
+    pub riichi: bool,             // リーチ宣言牌
+}
+
+impl Discard {
+    /// 鳴かれて河から副露に移った牌か
+    pub fn is_called(&self) -> bool {
+        self.called_by.is_some()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Player {
@@ -8,11 +24,14 @@ pub struct Player {
     pub hand: Hand,
     pub score: i32,
     pub is_dealer: bool,
-    pub discards: Vec<Tile>,
+    pub discards: Vec<Discard>,
     pub is_riichi: bool,
     pub riichi_turn: Option<usize>, // リーチ宣言したターン
     pub ippatsu: bool,               // 一発フラグ
     pub double_riichi: bool,         // ダブル立直
+    pub temporary_furiten: bool,     // 同巡内フリテン（次の自分の打牌まで）
+    pub riichi_furiten: bool,        // リーチ後の見逃し（局が終わるまで）
+    pub kita: u32,                   // 抜きドラにした北の枚数（三人麻雀）
 }
 
 impl Player {
@@ -28,22 +47,37 @@ impl Player {
             riichi_turn: None,
             ippatsu: false,
             double_riichi: false,
+            temporary_furiten: false,
+            riichi_furiten: false,
+            kita: 0,
         }
     }
 
     pub fn draw_tile(&mut self, tile: Tile) {
-        self.hand.add_tile(tile);
+        self.hand.draw_tile(tile);
     }
 
     pub fn discard_tile(&mut self, tile: Tile) -> bool {
-        if self.hand.remove_tile(&tile) {
-            self.discards.push(tile);
-            true
-        } else {
-            false
+        match self.hand.discard(&tile) {
+            Some(tsumogiri) => {
+                // 宣言牌の次の打牌で一発は消える
+                if self.riichi_turn.is_some_and(|turn| self.discards.len() > turn) {
+                    self.ippatsu = false;
+                }
+                let riichi = self.is_declaring_riichi();
+                self.discards.push(Discard { tile, called_by: None, tsumogiri, riichi });
+                self.temporary_furiten = false;
+                true
+            }
+            None => false,
         }
     }
 
+    /// リーチを宣言して宣言牌をまだ打牌していない
+    fn is_declaring_riichi(&self) -> bool {
+        self.riichi_turn == Some(self.discards.len())
+    }
+
     pub fn can_win(&self, tile: &Tile) -> bool {
         self.hand.can_win(tile)
     }
@@ -55,7 +89,7 @@ impl Player {
     pub fn get_discards_string(&self) -> String {
         self.discards
             .iter()
-            .map(|t| t.to_string())
+            .map(|d| d.tile.to_string())
             .collect::<Vec<_>>()
             .join(" ")
     }
@@ -110,31 +144,71 @@ impl Player {
         self.riichi_turn = Some(turn);
         self.ippatsu = true;
 
-        // 供託1000点を支払う
-        self.subtract_score(1000);
-
+        // 供託1000点は宣言牌が通ってから支払う（Game::accept_riichi）
         true
     }
 
+    /// 次の局に向けて手牌・河・リーチ状態を初期化（点数は持ち越し）
+    pub fn reset_for_new_hand(&mut self, is_dealer: bool) {
+        self.hand = Hand::new();
+        self.is_dealer = is_dealer;
+        self.discards.clear();
+        self.is_riichi = false;
+        self.riichi_turn = None;
+        self.ippatsu = false;
+        self.double_riichi = false;
+        self.temporary_furiten = false;
+        self.riichi_furiten = false;
+        self.kita = 0;
+    }
+
+    /// 自分の河に和了牌がある（捨て牌フリテン）
+    pub fn is_discard_furiten(&self) -> bool {
+        let waits = self.hand.waits();
+        self.discards
+            .iter()
+            .any(|discard| waits.iter().any(|wait| wait.index() == discard.tile.index()))
+    }
+
+    /// 河が么九牌のみで一度も鳴かれていない（流し満貫）
+    pub fn is_nagashi(&self) -> bool {
+        !self.discards.is_empty()
+            && self.discards.iter().all(|discard| {
+                !discard.is_called()
+                    && matches!(discard.tile.tile_type, TileType::Honor(_) | TileType::Number { value: 1 | 9, .. })
+            })
+    }
+
+    /// フリテンか（ロン和了できない）
+    pub fn is_furiten(&self) -> bool {
+        self.temporary_furiten || self.riichi_furiten || self.is_discard_furiten()
+    }
+
+    /// 和了牌を見逃した
+    pub fn miss_win(&mut self) {
+        self.temporary_furiten = true;
+        if self.is_riichi {
+            self.riichi_furiten = true;
+        }
+    }
+
     /// 一発フラグを消す（鳴きがあった場合など）
     pub fn clear_ippatsu(&mut self) {
         self.ippatsu = false;
     }
 
-    /// リーチ後の打牌チェック（ツモ切りのみ）
+    /// リーチ後の打牌チェック（宣言牌はテンパイを保つ牌、以降はツモ切りのみ）
     pub fn can_discard_after_riichi(&self, tile: &Tile) -> bool {
         if !self.is_riichi {
             return true; // リーチしていない場合は制限なし
         }
 
-        // リーチ後は最後にツモった牌のみ打牌可能
-        // 簡易実装: 手牌の最後の牌のみ打牌可能とする
-        let tiles = self.hand.get_tiles();
-        if let Some(last_tile) = tiles.last() {
-            last_tile == tile
-        } else {
-            false
+        if self.is_declaring_riichi() {
+            let mut hand = self.hand.clone();
+            return hand.remove_tile(tile) && hand.is_tenpai();
         }
+
+        self.hand.drawn_tile() == Some(*tile)
     }
 }
 
@@ -165,4 +239,82 @@ mod tests {
         assert_eq!(player.tile_count(), 0);
         assert_eq!(player.discards.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ippatsu_cleared_after_next_discard() {
+        let mut player = Player::new(0, "Test".to_string());
+        for tile in Tile::parse_tiles("123m 456m 789p 35s 55s 9s").unwrap() {
+            player.draw_tile(tile);
+        }
+
+        assert!(player.declare_riichi(0));
+        assert!(player.ippatsu);
+
+        // 宣言牌では一発は消えない
+        assert!(player.discard_tile(Tile::new_number(Suit::Sou, 9, false)));
+        assert!(player.ippatsu);
+
+        player.draw_tile(Tile::new_number(Suit::Pin, 1, false));
+        assert!(player.discard_tile(Tile::new_number(Suit::Pin, 1, false)));
+        assert!(!player.ippatsu);
+    }
+
+    #[test]
+    fn test_furiten() {
+        let mut player = Player::new(0, "Test".to_string());
+        for tile in Tile::parse_tiles("123m 456m 789p 45s 55s 7s").unwrap() {
+            player.draw_tile(tile);
+        }
+
+        // 3s6s待ちなので6sを捨てていればフリテン
+        assert!(player.discard_tile(Tile::new_number(Suit::Sou, 7, false)));
+        assert!(!player.is_furiten());
+        player.draw_tile(Tile::new_number(Suit::Sou, 6, false));
+        assert!(player.discard_tile(Tile::new_number(Suit::Sou, 6, false)));
+        assert!(player.is_discard_furiten());
+
+        // 同巡内フリテンは自分の打牌で解除、リーチ後の見逃しは解除されない
+        let mut player = Player::new(1, "Test".to_string());
+        player.miss_win();
+        assert!(player.is_furiten());
+        player.draw_tile(Tile::new_number(Suit::Man, 1, false));
+        assert!(player.discard_tile(Tile::new_number(Suit::Man, 1, false)));
+        assert!(!player.is_furiten());
+
+        player.is_riichi = true;
+        player.miss_win();
+        player.draw_tile(Tile::new_number(Suit::Man, 1, false));
+        assert!(player.discard_tile(Tile::new_number(Suit::Man, 1, false)));
+        assert!(player.is_furiten());
+    }
+
+    #[test]
+    fn test_tsumogiri_and_riichi_lock() {
+        let mut player = Player::new(0, "Test".to_string());
+        for tile in Tile::parse_tiles("123m 456m 789p 35s 55s").unwrap() {
+            player.hand.add_tile(tile);
+        }
+        let nine_sou = Tile::new_number(Suit::Sou, 9, false);
+        let one_man = Tile::new_number(Suit::Man, 1, false);
+
+        // ツモ牌は手牌の並びとは別に覚えている
+        player.draw_tile(nine_sou);
+        assert_eq!(player.hand.drawn_tile(), Some(nine_sou));
+        assert_eq!(player.hand.held_tiles().len(), 13);
+        assert!(player.get_hand_string().ends_with(" 9s"));
+
+        // 宣言牌はテンパイを崩さない牌のみ
+        assert!(player.declare_riichi(0));
+        assert!(!player.can_discard_after_riichi(&one_man));
+        assert!(player.can_discard_after_riichi(&nine_sou));
+        assert!(player.discard_tile(nine_sou));
+        assert!(player.discards[0].tsumogiri && player.discards[0].riichi);
+
+        // リーチ後はツモ切りのみ
+        player.draw_tile(Tile::new_number(Suit::Pin, 1, false));
+        assert!(!player.can_discard_after_riichi(&one_man));
+        assert!(player.can_discard_after_riichi(&Tile::new_number(Suit::Pin, 1, false)));
+        assert!(player.discard_tile(Tile::new_number(Suit::Pin, 1, false)));
+        assert!(!player.discards[1].riichi);
+    }
+}
@@ -0,0 +1,195 @@
+//! 対局ルールの設定
+
+use serde::{Deserialize, Serialize};
+
+/// 複数人が同じ牌でロンした場合の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultipleRon {
+    Allowed,  // ダブロン・トリプルロンを認める
+    HeadBump, // 頭ハネ（放銃者の下家から近い1人のみ）
+}
+
+/// 対局の長さ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameLength {
+    EastOnly, // 東風戦
+    HalfGame, // 半荘戦（東・南）
+    FullGame, // 一荘戦（東・南・西・北）
+}
+
+impl GameLength {
+    /// 通常の場の数
+    pub fn wind_count(&self) -> usize {
+        match self {
+            GameLength::EastOnly => 1,
+            GameLength::HalfGame => 2,
+            GameLength::FullGame => 4,
+        }
+    }
+}
+
+/// 槓ドラをめくるタイミング
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KanDoraTiming {
+    Immediate,    // カンの直後（嶺上牌のツモ前）
+    AfterDiscard, // カンした人の打牌の後
+}
+
+/// 三人麻雀のツモ和了で、いない北家の支払い分の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SanmaTsumo {
+    TsumoLoss,      // ツモ損（北家の分は誰も払わない）
+    NorthBisection, // 北家折半（北家の分を2人で半分ずつ払う）
+}
+
+/// 途中流局の採用（それぞれ個別に切り替え可能）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbortiveDraws {
+    pub kyuushu_kyuuhai: bool, // 九種九牌
+    pub suufon_renda: bool,    // 四風連打
+    pub suucha_riichi: bool,   // 四家立直
+    pub suukaikan: bool,       // 四開槓
+    pub sanchahou: bool,       // 三家和
+}
+
+impl Default for AbortiveDraws {
+    fn default() -> Self {
+        Self {
+            kyuushu_kyuuhai: true,
+            suufon_renda: true,
+            suucha_riichi: true,
+            suukaikan: true,
+            sanchahou: true,
+        }
+    }
+}
+
+impl AbortiveDraws {
+    /// 途中流局なし
+    pub fn none() -> Self {
+        Self {
+            kyuushu_kyuuhai: false,
+            suufon_renda: false,
+            suucha_riichi: false,
+            suukaikan: false,
+            sanchahou: false,
+        }
+    }
+}
+
+/// 対局ルール
+///
+/// JSONなどから読み込む場合、省略した項目は既定値（天鳳に近いルール）になる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    pub players: usize,          // 対局人数（4人、または3人の三人麻雀）
+    pub sanma_tsumo: SanmaTsumo, // 三人麻雀のツモ和了の支払い
+    pub length: GameLength,
+    pub starting_score: i32,     // 配給原点
+    pub target_score: i32,       // 返し点（オーラス終了時に誰も届かなければ延長）
+    pub west_extension: bool,    // 延長戦（サドンデス）を行うか
+    pub uma: [i32; 4],           // 着順ごとのウマ（千点単位）
+    pub oka: i32,                // トップが受け取るオカ（返し点と配給原点の差×人数）
+    pub tobi: bool,              // 持ち点がマイナスになったら終了（飛び）
+    pub red_fives: [u8; 3],      // 萬子・筒子・索子それぞれの赤5の枚数
+    pub kuitan: bool,            // 喰いタン（副露した断么九）を認めるか
+    pub kiriage_mangan: bool,    // 4飜30符・3飜60符を満貫に切り上げるか
+    pub kazoe_yakuman: bool,     // 13飜以上を数え役満とするか（しなければ三倍満止まり）
+    pub multiple_yakuman: bool,  // 役満の複合を認めるか
+    pub double_yakuman: bool,    // 国士十三面・四暗刻単騎・純正九蓮をダブル役満とするか
+    pub multiple_ron: MultipleRon,
+    pub nagashi_mangan: bool,    // 流し満貫を採用するか
+    pub abortive_draws: AbortiveDraws,
+    pub closed_kan_dora: KanDoraTiming, // 暗槓
+    pub open_kan_dora: KanDoraTiming,   // 明槓・加槓
+    pub kokushi_rob_ankan: bool,        // 国士無双による暗槓の槍槓を認めるか
+    pub allow_kuikae: bool,             // 喰い替え（鳴いた牌・筋の牌の打牌）を認めるか
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::tenhou()
+    }
+}
+
+impl RuleSet {
+    /// 天鳳（鳳凰卓）に近いルール
+    pub fn tenhou() -> Self {
+        Self {
+            players: 4,
+            sanma_tsumo: SanmaTsumo::TsumoLoss,
+            length: GameLength::HalfGame,
+            starting_score: 25000,
+            target_score: 30000,
+            west_extension: true,
+            uma: [20, 10, -10, -20],
+            oka: 20000,
+            tobi: true,
+            red_fives: [1, 1, 1],
+            kuitan: true,
+            kiriage_mangan: false,
+            kazoe_yakuman: true,
+            multiple_yakuman: true,
+            double_yakuman: false,
+            multiple_ron: MultipleRon::Allowed,
+            nagashi_mangan: true,
+            abortive_draws: AbortiveDraws::default(),
+            closed_kan_dora: KanDoraTiming::Immediate,
+            open_kan_dora: KanDoraTiming::AfterDiscard,
+            kokushi_rob_ankan: true,
+            allow_kuikae: false,
+        }
+    }
+
+    /// Mリーグに近いルール（ウマ10-30・頭ハネ・途中流局なし・飛びなし・西入なし）
+    pub fn m_league() -> Self {
+        Self {
+            west_extension: false,
+            uma: [30, 10, -10, -30],
+            tobi: false,
+            multiple_yakuman: false,
+            multiple_ron: MultipleRon::HeadBump,
+            abortive_draws: AbortiveDraws::none(),
+            ..Self::tenhou()
+        }
+    }
+
+    /// 天鳳の三人打ちに近いルール（萬子の2〜8なし、北抜き、ツモ損）
+    pub fn sanma() -> Self {
+        Self {
+            players: 3,
+            starting_score: 35000,
+            target_score: 40000,
+            uma: [20, 0, -20, 0],
+            oka: 15000,
+            red_fives: [0, 1, 1],
+            ..Self::tenhou()
+        }
+    }
+
+    /// 三人麻雀か
+    pub fn is_sanma(&self) -> bool {
+        self.players == 3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_set_serde() {
+        let rules = RuleSet::m_league();
+        let json = serde_json::to_string(&rules).unwrap();
+        assert_eq!(serde_json::from_str::<RuleSet>(&json).unwrap(), rules);
+
+        // 省略した項目は既定値
+        let rules: RuleSet = serde_json::from_str(r#"{"kuitan": false, "length": "EastOnly"}"#).unwrap();
+        assert!(!rules.kuitan);
+        assert_eq!(rules.length, GameLength::EastOnly);
+        assert_eq!(rules.starting_score, 25000);
+        assert_eq!(rules.abortive_draws, AbortiveDraws::default());
+    }
+}
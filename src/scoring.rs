@@ -1,8 +1,10 @@
-use crate::tile::{Tile, TileType, Honor, Suit};
-use crate::hand::{Hand, MeldType};
+use crate::tile::{Tile, TileType, Honor, Suit, TILE_KINDS};
+use crate::hand::{Decomposition, Hand, HandSet, SetKind, WaitShape};
+use crate::rules::{RuleSet, SanmaTsumo};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Yaku {
     // 一飜役
     Riichi,
@@ -52,232 +54,493 @@ pub enum Yaku {
     Chiihou,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ScoringResult {
     pub han: u32,
     pub fu: u32,
+    pub fu_items: Vec<FuItem>, // 符の内訳（切り上げ前）
     pub yaku: Vec<Yaku>,
+    pub dora: u32,     // ドラ（槓ドラ含む）
+    pub ura_dora: u32, // 裏ドラ（リーチ時のみ）
+    pub aka_dora: u32, // 赤ドラ
+    pub kita: u32,     // 抜きドラ（三人麻雀の北）
     pub base_points: u32,
     pub total_points: u32,
 }
 
-pub struct ScoringEngine;
+/// 支払い1件分（from が to に points 点払う）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub from: usize,
+    pub to: usize,
+    pub points: u32,
+}
 
-impl ScoringEngine {
-    pub fn calculate_score(hand: &Hand, winning_tile: &Tile, is_tsumo: bool, is_dealer: bool) -> Option<ScoringResult> {
-        let mut yaku = Vec::new();
-        let mut han = 0;
-        let is_menzen = hand.get_melds().is_empty();
+/// 和了による点数移動
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payment {
+    pub winner: usize,
+    pub transfers: Vec<Transfer>,
+    pub riichi_deposit: u32, // 和了者が受け取る供託
+}
 
-        // 手牌情報の取得
-        let mut all_tiles = hand.get_tiles().clone();
-        all_tiles.push(*winning_tile);
+impl Payment {
+    /// 指定プレイヤーの収支
+    pub fn delta(&self, player: usize) -> i32 {
+        let mut delta = 0;
+        for transfer in &self.transfers {
+            if transfer.from == player {
+                delta -= transfer.points as i32;
+            }
+            if transfer.to == player {
+                delta += transfer.points as i32;
+            }
+        }
+        if player == self.winner {
+            delta += self.riichi_deposit as i32;
+        }
+        delta
+    }
+}
+
+/// 100点単位に切り上げ
+fn round_up_100(points: u32) -> u32 {
+    points.div_ceil(100) * 100
+}
+
+/// 符の発生理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FuReason {
+    Base,            // 副底
+    Chiitoitsu,      // 七対子（25符固定）
+    MenzenRon,       // 門前加符
+    Tsumo,           // ツモ符
+    OpenPinfu,       // 喰い平和形（30符に繰り上げ）
+    Set(HandSet),    // 刻子・槓子
+    Pair(Honor),     // 役牌の雀頭（連風牌は2つ付く）
+    Wait(WaitShape), // 嵌張・辺張・単騎待ち
+}
+
+/// 符の内訳の1項目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuItem {
+    pub reason: FuReason,
+    pub fu: u32,
+}
+
+/// 和了時の状況（状況役・風牌の判定用）
+#[derive(Debug, Clone)]
+pub struct WinContext {
+    pub is_tsumo: bool,
+    pub is_riichi: bool,
+    pub is_double_riichi: bool,
+    pub is_ippatsu: bool,
+    pub is_last_tile: bool,  // 海底摸月・河底撈魚
+    pub is_rinshan: bool,    // 嶺上開花
+    pub is_chankan: bool,    // 槍槓
+    pub is_first_turn: bool, // 天和・地和（鳴きのない第一ツモ）
+    pub seat_wind: Honor,    // 自風
+    pub round_wind: Honor,   // 場風
+    pub dora_indicators: Vec<Tile>,
+    pub ura_dora_indicators: Vec<Tile>, // リーチ和了時のみ参照
+    pub kita: u32,                      // 抜いた北の枚数
+    pub rules: RuleSet,                 // 喰いタン・切り上げ満貫・役満の扱い
+}
 
-        // 役満チェック
-        if Self::check_kokushi(&all_tiles) {
-            yaku.push(Yaku::Kokushi);
-            han += 13;
+impl WinContext {
+    /// 状況役なしの和了状況を作成
+    pub fn new(is_tsumo: bool, seat_wind: Honor, round_wind: Honor) -> Self {
+        Self {
+            is_tsumo,
+            is_riichi: false,
+            is_double_riichi: false,
+            is_ippatsu: false,
+            is_last_tile: false,
+            is_rinshan: false,
+            is_chankan: false,
+            is_first_turn: false,
+            seat_wind,
+            round_wind,
+            dora_indicators: Vec::new(),
+            ura_dora_indicators: Vec::new(),
+            kita: 0,
+            rules: RuleSet::default(),
         }
+    }
+
+    /// 親（東家）か
+    pub fn is_dealer(&self) -> bool {
+        self.seat_wind == Honor::Ton
+    }
+}
+
+pub struct ScoringEngine;
 
-        if Self::check_suuankou(hand, winning_tile, is_tsumo) {
-            yaku.push(Yaku::Suuankou);
-            han += 13;
+/// 和了形の種類
+enum WinShape<'a> {
+    Normal(&'a Decomposition), // 4面子1雀頭
+    Chiitoitsu,                // 七対子
+    Kokushi,                   // 国士無双
+}
+
+impl ScoringEngine {
+    /// 和了形として取り得る全ての解釈を評価し、最も高い点数のものを返す
+    pub fn calculate_score(hand: &Hand, winning_tile: &Tile, context: &WinContext) -> Option<ScoringResult> {
+        // 手牌情報の取得（副露も含めた全ての牌）
+        let mut concealed_tiles = hand.get_tiles().clone();
+        concealed_tiles.push(*winning_tile);
+        let mut all_tiles = concealed_tiles.clone();
+        for meld in hand.get_melds() {
+            all_tiles.extend(meld.tiles.iter().copied());
         }
 
-        if Self::check_daisangen(hand) {
-            yaku.push(Yaku::Daisangen);
-            han += 13;
+        let decompositions = hand.decompositions(winning_tile, context.is_tsumo);
+        let mut shapes: Vec<WinShape> = decompositions.iter().map(WinShape::Normal).collect();
+        if hand.get_melds().is_empty() {
+            if Self::check_chiitoitsu(&concealed_tiles) {
+                shapes.push(WinShape::Chiitoitsu);
+            }
+            if Self::check_kokushi(&concealed_tiles) {
+                shapes.push(WinShape::Kokushi);
+            }
         }
 
-        if Self::check_tsuuiisou(&all_tiles) {
-            yaku.push(Yaku::Tsuuiisou);
-            han += 13;
+        shapes
+            .iter()
+            .filter_map(|shape| Self::evaluate(hand, &all_tiles, shape, context))
+            .max_by_key(|result| (result.total_points, result.han, result.fu))
+    }
+
+    /// 1つの和了形の解釈について役と点数を計算
+    fn evaluate(
+        hand: &Hand,
+        all_tiles: &[Tile],
+        shape: &WinShape,
+        context: &WinContext,
+    ) -> Option<ScoringResult> {
+        let mut yaku = Vec::new();
+        let mut han = 0;
+        let is_menzen = hand.get_melds().iter().all(|meld| !meld.is_open);
+        let is_tsumo = context.is_tsumo;
+        let is_dealer = context.is_dealer();
+        let rules = &context.rules;
+
+        // 役満チェック（倍数はダブル役満なら2）
+        let double = if rules.double_yakuman { 2 } else { 1 };
+        let mut yakuman = Vec::new();
+        // 天和・地和は暗槓を含め副露がないこと
+        if context.is_first_turn && is_tsumo && hand.get_melds().is_empty() {
+            yakuman.push((if is_dealer { Yaku::Tenhou } else { Yaku::Chiihou }, 1));
         }
 
-        if Self::check_ryuuiisou(&all_tiles) {
-            yaku.push(Yaku::Ryuuiisou);
-            han += 13;
+        match shape {
+            WinShape::Kokushi => {
+                let multiplier = if Self::is_kokushi_13_wait(hand) { double } else { 1 };
+                yakuman.push((Yaku::Kokushi, multiplier));
+            }
+            WinShape::Normal(decomposition) => {
+                if Self::check_suuankou(decomposition) {
+                    let multiplier = if decomposition.wait() == WaitShape::Tanki { double } else { 1 };
+                    yakuman.push((Yaku::Suuankou, multiplier));
+                }
+
+                if Self::check_daisangen(decomposition) {
+                    yakuman.push((Yaku::Daisangen, 1));
+                }
+
+                if Self::check_chuuren(all_tiles, is_menzen) {
+                    let multiplier = if Self::is_junsei_chuuren(hand) { double } else { 1 };
+                    yakuman.push((Yaku::Chuuren, multiplier));
+                }
+
+                if Self::count_kans(decomposition) == 4 {
+                    yakuman.push((Yaku::Suukantsu, 1));
+                }
+            }
+            WinShape::Chiitoitsu => {}
+        }
+
+        if Self::check_tsuuiisou(all_tiles) {
+            yakuman.push((Yaku::Tsuuiisou, 1));
         }
 
-        if Self::check_chinroutou(&all_tiles) {
-            yaku.push(Yaku::Chinroutou);
-            han += 13;
+        if Self::check_ryuuiisou(all_tiles) {
+            yakuman.push((Yaku::Ryuuiisou, 1));
         }
 
-        if Self::check_chuuren(&all_tiles, is_menzen) {
-            yaku.push(Yaku::Chuuren);
-            han += 13;
+        if Self::check_chinroutou(all_tiles) {
+            yakuman.push((Yaku::Chinroutou, 1));
         }
 
         // 役満がある場合は他の役をチェックしない
-        if han >= 13 {
-            let fu = Self::calculate_fu(hand, winning_tile, is_tsumo);
-            let base_points = 8000; // 役満
-            let total_points = Self::calculate_total_points(base_points, is_dealer, is_tsumo);
+        if !yakuman.is_empty() {
+            // 複合を認めなければ最も高い役満1つ分
+            let multipliers = yakuman.iter().map(|&(_, multiplier)| multiplier);
+            let multiplier = if rules.multiple_yakuman { multipliers.sum() } else { multipliers.max().unwrap_or(1) };
+            yaku.extend(yakuman.into_iter().map(|(yaku, _)| yaku));
+            han = 13 * multiplier;
+
+            let fu_items = Self::calculate_fu(shape, context, is_menzen, false);
+            let fu = Self::round_fu(&fu_items);
+            let base_points = 8000 * multiplier; // 役満
+            let total_points = Self::calculate_total_points(base_points, is_dealer, is_tsumo, &context.rules);
 
             return Some(ScoringResult {
                 han,
                 fu,
+                fu_items,
                 yaku,
+                dora: 0,
+                ura_dora: 0,
+                aka_dora: 0,
+                kita: 0,
                 base_points,
                 total_points,
             });
         }
 
-        // 七対子チェック（特殊形）
-        if is_menzen && Self::check_chiitoitsu(&all_tiles) {
+        // 状況役
+        let (situational_yaku, situational_han) = Self::evaluate_situation(context, is_menzen);
+        yaku.extend(situational_yaku);
+        han += situational_han;
+
+        // 七対子（特殊形）
+        if let WinShape::Chiitoitsu = shape {
             yaku.push(Yaku::Chiitoitsu);
             han += 2;
         }
 
         // 通常役のチェック
-        if Self::check_tanyao(&all_tiles) {
+        if Self::check_tanyao(all_tiles) && (is_menzen || context.rules.kuitan) {
             yaku.push(Yaku::Tanyao);
             han += 1;
         }
 
-        if is_menzen && Self::check_pinfu(hand, winning_tile) {
-            yaku.push(Yaku::Pinfu);
+        if is_tsumo && is_menzen {
+            yaku.push(Yaku::Tsumo);
             han += 1;
         }
 
-        if is_tsumo && is_menzen {
-            yaku.push(Yaku::Tsumo);
+        if let WinShape::Normal(decomposition) = shape {
+            let (normal_yaku, normal_han) = Self::evaluate_sets(decomposition, context, is_menzen);
+            yaku.extend(normal_yaku);
+            han += normal_han;
+        }
+
+        // 混一色
+        if Self::check_honitsu(all_tiles) {
+            yaku.push(Yaku::Honitsu);
+            han += if is_menzen { 3 } else { 2 };
+        }
+
+        // 清一色
+        if Self::check_chinitsu(all_tiles) {
+            yaku.push(Yaku::Chinitsu);
+            han += if is_menzen { 6 } else { 5 };
+        }
+
+        if han == 0 {
+            return None; // 役なし
+        }
+
+        // ドラは役に数えないので役の有無を判定した後に加算
+        let dora = Self::count_dora(all_tiles, &context.dora_indicators, &context.rules);
+        let ura_dora = if context.is_riichi || context.is_double_riichi {
+            Self::count_dora(all_tiles, &context.ura_dora_indicators, &context.rules)
+        } else {
+            0
+        };
+        let aka_dora = all_tiles.iter().filter(|tile| tile.is_red).count() as u32;
+        let kita = Self::count_kita(context);
+        han += dora + ura_dora + aka_dora + kita;
+
+        let fu_items = Self::calculate_fu(shape, context, is_menzen, yaku.contains(&Yaku::Pinfu));
+        let fu = Self::round_fu(&fu_items);
+        let base_points = Self::calculate_base_points(han, fu, &context.rules);
+        let total_points = Self::calculate_total_points(base_points, is_dealer, is_tsumo, &context.rules);
+
+        Some(ScoringResult {
+            han,
+            fu,
+            fu_items,
+            yaku,
+            dora,
+            ura_dora,
+            aka_dora,
+            kita,
+            base_points,
+            total_points,
+        })
+    }
+
+    /// 表示牌から数えたドラの枚数
+    fn count_dora(tiles: &[Tile], indicators: &[Tile], rules: &RuleSet) -> u32 {
+        indicators
+            .iter()
+            .map(|indicator| {
+                let dora = if rules.is_sanma() {
+                    indicator.indicated_dora_sanma()
+                } else {
+                    indicator.indicated_dora()
+                };
+                tiles.iter().filter(|tile| tile.index() == dora.index()).count() as u32
+            })
+            .sum()
+    }
+
+    /// 抜いた北をドラとして数える（北がドラなら1枚につきさらに加算）
+    fn count_kita(context: &WinContext) -> u32 {
+        if context.kita == 0 {
+            return 0;
+        }
+        let north = [Tile::new_honor(Honor::Pei)];
+        let mut per_tile = 1 + Self::count_dora(&north, &context.dora_indicators, &context.rules);
+        if context.is_riichi || context.is_double_riichi {
+            per_tile += Self::count_dora(&north, &context.ura_dora_indicators, &context.rules);
+        }
+        context.kita * per_tile
+    }
+
+    /// リーチ・一発・海底などの状況役を判定
+    fn evaluate_situation(context: &WinContext, is_menzen: bool) -> (Vec<Yaku>, u32) {
+        let mut yaku = Vec::new();
+        let mut han = 0;
+
+        if is_menzen && context.is_double_riichi {
+            yaku.push(Yaku::DoubleRiichi);
+            han += 2;
+        } else if is_menzen && context.is_riichi {
+            yaku.push(Yaku::Riichi);
+            han += 1;
+        }
+
+        if (context.is_riichi || context.is_double_riichi) && context.is_ippatsu {
+            yaku.push(Yaku::Ippatsu);
+            han += 1;
+        }
+
+        if context.is_tsumo && context.is_rinshan {
+            yaku.push(Yaku::Rinshan);
+            han += 1;
+        } else if context.is_last_tile {
+            // 嶺上牌での和了は海底にならない
+            yaku.push(if context.is_tsumo { Yaku::Haitei } else { Yaku::Houtei });
+            han += 1;
+        }
+
+        if !context.is_tsumo && context.is_chankan {
+            yaku.push(Yaku::Chankan);
+            han += 1;
+        }
+
+        (yaku, han)
+    }
+
+    /// 面子構成に依存する役を判定
+    fn evaluate_sets(decomposition: &Decomposition, context: &WinContext, is_menzen: bool) -> (Vec<Yaku>, u32) {
+        let mut yaku = Vec::new();
+        let mut han = 0;
+
+        if is_menzen && Self::check_pinfu(decomposition, context) {
+            yaku.push(Yaku::Pinfu);
             han += 1;
         }
 
         if is_menzen {
-            if Self::check_iipeikou(&all_tiles) {
+            // 二盃口は一盃口を含むので、どちらか一方のみ
+            if Self::check_ryanpeikou(decomposition) {
+                yaku.push(Yaku::Ryanpeikou);
+                han += 3;
+            } else if Self::check_iipeikou(decomposition) {
                 yaku.push(Yaku::Iipeikou);
                 han += 1;
             }
-
-            if Self::check_ryanpeikou(&all_tiles) {
-                // 二盃口は一盃口を含むので、一盃口を削除
-                yaku.retain(|y| y != &Yaku::Iipeikou);
-                yaku.push(Yaku::Ryanpeikou);
-                han = han.saturating_sub(1) + 3; // 一盃口を引いて二盃口を足す
-            }
         }
 
-        // 役牌チェック
-        for honor in [Honor::Haku, Honor::Hatsu, Honor::Chun] {
-            if Self::check_yakuhai(hand, honor) {
+        // 役牌チェック（連風牌は自風・場風で2つ付く）
+        for honor in [context.seat_wind, context.round_wind, Honor::Haku, Honor::Hatsu, Honor::Chun] {
+            if Self::check_yakuhai(decomposition, honor) {
                 yaku.push(Yaku::Yakuhai(honor));
                 han += 1;
             }
         }
 
         // 三色同順
-        if let Some(h) = Self::check_sanshoku_doujun(&all_tiles, hand) {
+        if Self::check_sanshoku_doujun(decomposition).is_some() {
             yaku.push(Yaku::SanshokuDoujun);
             han += if is_menzen { 2 } else { 1 };
         }
 
         // 一気通貫
-        if Self::check_ittsu(&all_tiles, hand) {
+        if Self::check_ittsu(decomposition) {
             yaku.push(Yaku::Ittsu);
             han += if is_menzen { 2 } else { 1 };
         }
 
-        // 混全帯么九
-        if Self::check_chanta(&all_tiles, hand) {
+        // 純全帯么九があれば混全帯么九は付かない
+        if Self::check_junchan(decomposition) {
+            yaku.push(Yaku::Junchan);
+            han += if is_menzen { 3 } else { 2 };
+        } else if Self::check_chanta(decomposition) {
             yaku.push(Yaku::Chanta);
             han += if is_menzen { 2 } else { 1 };
         }
 
-        // 純全帯么九
-        if Self::check_junchan(&all_tiles, hand) {
-            // 純チャンがあればチャンタを削除
-            yaku.retain(|y| y != &Yaku::Chanta);
-            yaku.push(Yaku::Junchan);
-            han = han.saturating_sub(if is_menzen { 2 } else { 1 }) + if is_menzen { 3 } else { 2 };
-        }
-
         // 対々和
-        if Self::check_toitoi(hand) {
+        if Self::check_toitoi(decomposition) {
             yaku.push(Yaku::Toitoi);
             han += 2;
         }
 
         // 三暗刻
-        if Self::check_sanankou(hand, winning_tile, is_tsumo) {
+        if Self::check_sanankou(decomposition) {
             yaku.push(Yaku::Sanankou);
             han += 2;
         }
 
         // 三色同刻
-        if Self::check_sanshoku_doukou(hand) {
+        if Self::check_sanshoku_doukou(decomposition) {
             yaku.push(Yaku::SanshokuDoukou);
             han += 2;
         }
 
         // 小三元
-        if Self::check_shousangen(hand) {
+        if Self::check_shousangen(decomposition) {
             yaku.push(Yaku::Shousangen);
             han += 2;
         }
 
-        // 混一色
-        if Self::check_honitsu(&all_tiles) {
-            yaku.push(Yaku::Honitsu);
-            han += if is_menzen { 3 } else { 2 };
-        }
-
-        // 清一色
-        if Self::check_chinitsu(&all_tiles) {
-            // 清一色があれば混一色を削除
-            yaku.retain(|y| y != &Yaku::Honitsu);
-            yaku.push(Yaku::Chinitsu);
-            han = han.saturating_sub(if is_menzen { 3 } else { 2 }) + if is_menzen { 6 } else { 5 };
-        }
-
-        if han == 0 {
-            return None; // 役なし
+        // 三槓子
+        if Self::count_kans(decomposition) == 3 {
+            yaku.push(Yaku::Sankantsu);
+            han += 2;
         }
 
-        let fu = Self::calculate_fu(hand, winning_tile, is_tsumo);
-        let base_points = Self::calculate_base_points(han, fu);
-        let total_points = Self::calculate_total_points(base_points, is_dealer, is_tsumo);
-
-        Some(ScoringResult {
-            han,
-            fu,
-            yaku,
-            base_points,
-            total_points,
-        })
+        (yaku, han)
     }
-    
+
     // タンヤオ（断么九）
     fn check_tanyao(tiles: &[Tile]) -> bool {
         tiles.iter().all(|tile| match tile.tile_type {
-            TileType::Number { value, .. } => value >= 2 && value <= 8,
+            TileType::Number { value, .. } => (2..=8).contains(&value),
             TileType::Honor(_) => false,
         })
     }
 
     // ピンフ（平和）
-    fn check_pinfu(hand: &Hand, _winning_tile: &Tile) -> bool {
-        // 副露なし、全て順子、雀頭が役牌でない、両面待ち
-        // 簡易実装: 副露なしのみチェック
-        hand.get_melds().is_empty()
+    fn check_pinfu(decomposition: &Decomposition, context: &WinContext) -> bool {
+        // 副露なし（呼び出し側で確認）、全て順子、雀頭が役牌でない、両面待ち
+        decomposition.sets.iter().all(|set| set.kind == SetKind::Sequence)
+            && !Self::is_yakuhai_tile(&decomposition.pair, context)
+            && decomposition.wait() == WaitShape::Ryanmen
     }
 
     // 役牌
-    fn check_yakuhai(hand: &Hand, honor: Honor) -> bool {
-        for meld in hand.get_melds() {
-            if let MeldType::Pon | MeldType::Kan = meld.meld_type {
-                if !meld.tiles.is_empty() {
-                    if let TileType::Honor(h) = meld.tiles[0].tile_type {
-                        if h == honor {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-        false
+    fn check_yakuhai(decomposition: &Decomposition, honor: Honor) -> bool {
+        decomposition
+            .sets
+            .iter()
+            .any(|set| set.is_triplet() && set.tile.tile_type == TileType::Honor(honor))
     }
 
     // 七対子
@@ -288,93 +551,170 @@ impl ScoringEngine {
 
         let mut tile_map = HashMap::new();
         for tile in tiles {
-            *tile_map.entry(*tile).or_insert(0) += 1;
+            *tile_map.entry(tile.normalized()).or_insert(0) += 1;
         }
 
         let pairs: Vec<_> = tile_map.iter().filter(|(_, &count)| count == 2).collect();
         pairs.len() == 7
     }
 
+    /// 同じ順子の組の数（一盃口・二盃口の判定用）
+    fn count_identical_sequence_pairs(decomposition: &Decomposition) -> usize {
+        let mut counts = [0usize; TILE_KINDS];
+        for set in &decomposition.sets {
+            if set.kind == SetKind::Sequence {
+                counts[set.tile.index()] += 1;
+            }
+        }
+        counts.iter().map(|count| count / 2).sum()
+    }
+
     // 一盃口
-    fn check_iipeikou(tiles: &[Tile]) -> bool {
-        // 同じ順子が2組
-        // 簡易実装: 省略（複雑なので後で実装）
-        false
+    fn check_iipeikou(decomposition: &Decomposition) -> bool {
+        // 同じ順子が2組（門前は呼び出し側で確認）
+        Self::count_identical_sequence_pairs(decomposition) >= 1
     }
 
     // 二盃口
-    fn check_ryanpeikou(tiles: &[Tile]) -> bool {
+    fn check_ryanpeikou(decomposition: &Decomposition) -> bool {
         // 同じ順子が2組x2
-        // 簡易実装: 省略
-        false
+        Self::count_identical_sequence_pairs(decomposition) == 2
     }
 
     // 三色同順
-    fn check_sanshoku_doujun(tiles: &[Tile], hand: &Hand) -> Option<u8> {
+    fn check_sanshoku_doujun(decomposition: &Decomposition) -> Option<u8> {
         // 萬子・筒子・索子で同じ数の順子
-        // 簡易実装: 省略
-        None
+        (1..=7).find(|&value| {
+            [Suit::Man, Suit::Pin, Suit::Sou].iter().all(|&suit| {
+                let start = Tile::new_number(suit, value, false);
+                decomposition
+                    .sets
+                    .iter()
+                    .any(|set| set.kind == SetKind::Sequence && set.tile == start)
+            })
+        })
     }
 
     // 一気通貫
-    fn check_ittsu(tiles: &[Tile], hand: &Hand) -> bool {
+    fn check_ittsu(decomposition: &Decomposition) -> bool {
         // 同じ色で123・456・789の順子
-        // 簡易実装: 省略
-        false
+        [Suit::Man, Suit::Pin, Suit::Sou].iter().any(|&suit| {
+            [1, 4, 7].iter().all(|&value| {
+                let start = Tile::new_number(suit, value, false);
+                decomposition
+                    .sets
+                    .iter()
+                    .any(|set| set.kind == SetKind::Sequence && set.tile == start)
+            })
+        })
     }
 
     // 混全帯么九（チャンタ）
-    fn check_chanta(tiles: &[Tile], hand: &Hand) -> bool {
-        // 全ての面子と雀頭に么九牌が含まれる
-        // 簡易実装: 省略
-        false
+    fn check_chanta(decomposition: &Decomposition) -> bool {
+        // 全ての面子と雀頭に么九牌が含まれ、字牌と順子がある
+        let has_honor = Self::is_honor(&decomposition.pair)
+            || decomposition.sets.iter().any(|set| Self::is_honor(&set.tile));
+
+        has_honor && Self::all_blocks_contain(decomposition, Self::is_yaochu)
     }
 
     // 純全帯么九（ジュンチャン）
-    fn check_junchan(tiles: &[Tile], hand: &Hand) -> bool {
-        // 全ての面子と雀頭に老頭牌（1,9）が含まれる（字牌なし）
-        // 簡易実装: 省略
-        false
+    fn check_junchan(decomposition: &Decomposition) -> bool {
+        // 全ての面子と雀頭に老頭牌（1,9）が含まれ、順子がある（字牌なし）
+        Self::all_blocks_contain(decomposition, Self::is_terminal)
     }
 
-    // 対々和
-    fn check_toitoi(hand: &Hand) -> bool {
-        // 全ての面子が刻子
-        if hand.get_melds().len() + 4 != 4 {
-            return false;
-        }
+    /// 全ての面子と雀頭が条件を満たす牌を含み、順子が1組以上あるか
+    fn all_blocks_contain(decomposition: &Decomposition, predicate: fn(&Tile) -> bool) -> bool {
+        let has_sequence = decomposition.sets.iter().any(|set| set.kind == SetKind::Sequence);
 
-        hand.get_melds().iter().all(|meld| match meld.meld_type {
-            MeldType::Pon | MeldType::Kan => true,
-            _ => false,
-        })
+        has_sequence
+            && predicate(&decomposition.pair)
+            && decomposition
+                .sets
+                .iter()
+                .all(|set| set.tiles().iter().any(predicate))
     }
 
-    // 三暗刻
-    fn check_sanankou(hand: &Hand, _winning_tile: &Tile, is_tsumo: bool) -> bool {
-        // 暗刻が3組
-        // 簡易実装: 副露がない刻子が3組
-        let ankou_count = hand
-            .get_melds()
+    // 対々和
+    fn check_toitoi(decomposition: &Decomposition) -> bool {
+        // 全ての面子が刻子・槓子
+        decomposition.sets.iter().all(|set| set.is_triplet())
+    }
+
+    /// 暗刻（暗槓を含む）の数
+    fn count_concealed_triplets(decomposition: &Decomposition) -> usize {
+        decomposition
+            .sets
             .iter()
-            .filter(|meld| !meld.is_open && matches!(meld.meld_type, MeldType::Pon | MeldType::Kan))
-            .count();
+            .filter(|set| match set.kind {
+                SetKind::ConcealedTriplet => true,
+                SetKind::Kan => !set.is_open,
+                _ => false,
+            })
+            .count()
+    }
+
+    /// 槓子の数（暗槓・明槓・加槓）
+    fn count_kans(decomposition: &Decomposition) -> usize {
+        decomposition.sets.iter().filter(|set| set.kind == SetKind::Kan).count()
+    }
 
-        ankou_count >= 3
+    // 三暗刻
+    fn check_sanankou(decomposition: &Decomposition) -> bool {
+        Self::count_concealed_triplets(decomposition) == 3
     }
 
     // 三色同刻
-    fn check_sanshoku_doukou(hand: &Hand) -> bool {
+    fn check_sanshoku_doukou(decomposition: &Decomposition) -> bool {
         // 萬子・筒子・索子で同じ数の刻子
-        // 簡易実装: 省略
-        false
+        (1..=9).any(|value| {
+            [Suit::Man, Suit::Pin, Suit::Sou].iter().all(|&suit| {
+                let tile = Tile::new_number(suit, value, false);
+                decomposition
+                    .sets
+                    .iter()
+                    .any(|set| set.is_triplet() && set.tile == tile)
+            })
+        })
     }
 
     // 小三元
-    fn check_shousangen(hand: &Hand) -> bool {
+    fn check_shousangen(decomposition: &Decomposition) -> bool {
         // 三元牌の2組が刻子、1組が雀頭
-        // 簡易実装: 省略
-        false
+        let dragon_triplets = [Honor::Haku, Honor::Hatsu, Honor::Chun]
+            .iter()
+            .filter(|&&honor| Self::check_yakuhai(decomposition, honor))
+            .count();
+
+        dragon_triplets == 2 && Self::is_dragon(&decomposition.pair)
+    }
+
+    /// 字牌か
+    fn is_honor(tile: &Tile) -> bool {
+        matches!(tile.tile_type, TileType::Honor(_))
+    }
+
+    /// 三元牌か
+    fn is_dragon(tile: &Tile) -> bool {
+        matches!(tile.tile_type, TileType::Honor(Honor::Haku | Honor::Hatsu | Honor::Chun))
+    }
+
+    /// 役牌（三元牌・自風・場風）か
+    fn is_yakuhai_tile(tile: &Tile, context: &WinContext) -> bool {
+        Self::is_dragon(tile)
+            || tile.tile_type == TileType::Honor(context.seat_wind)
+            || tile.tile_type == TileType::Honor(context.round_wind)
+    }
+
+    /// 老頭牌（1,9）か
+    fn is_terminal(tile: &Tile) -> bool {
+        matches!(tile.tile_type, TileType::Number { value: 1 | 9, .. })
+    }
+
+    /// 么九牌（1,9,字牌）か
+    fn is_yaochu(tile: &Tile) -> bool {
+        Self::is_terminal(tile) || Self::is_honor(tile)
     }
 
     // 混一色
@@ -436,7 +776,7 @@ impl ScoringEngine {
 
         let mut tile_map = HashMap::new();
         for tile in tiles {
-            *tile_map.entry(*tile).or_insert(0) += 1;
+            *tile_map.entry(tile.normalized()).or_insert(0) += 1;
         }
 
         let mut has_pair = false;
@@ -458,39 +798,17 @@ impl ScoringEngine {
     }
 
     // 四暗刻
-    fn check_suuankou(hand: &Hand, _winning_tile: &Tile, is_tsumo: bool) -> bool {
-        // 暗刻が4組（ツモの場合のみ）
-        if !is_tsumo {
-            return false;
-        }
-
-        let ankou_count = hand
-            .get_melds()
-            .iter()
-            .filter(|meld| !meld.is_open && matches!(meld.meld_type, MeldType::Pon | MeldType::Kan))
-            .count();
-
-        ankou_count >= 4
+    fn check_suuankou(decomposition: &Decomposition) -> bool {
+        // ロン和了で完成した刻子は明刻なので、単騎待ちかツモの場合のみ成立
+        Self::count_concealed_triplets(decomposition) == 4
     }
 
     // 大三元
-    fn check_daisangen(hand: &Hand) -> bool {
+    fn check_daisangen(decomposition: &Decomposition) -> bool {
         // 三元牌（白発中）の3組が全て刻子
-        let mut sangenpai_count = 0;
-
-        for meld in hand.get_melds() {
-            if let MeldType::Pon | MeldType::Kan = meld.meld_type {
-                if !meld.tiles.is_empty() {
-                    if let TileType::Honor(h) = meld.tiles[0].tile_type {
-                        if matches!(h, Honor::Haku | Honor::Hatsu | Honor::Chun) {
-                            sangenpai_count += 1;
-                        }
-                    }
-                }
-            }
-        }
-
-        sangenpai_count == 3
+        [Honor::Haku, Honor::Hatsu, Honor::Chun]
+            .iter()
+            .all(|&honor| Self::check_yakuhai(decomposition, honor))
     }
 
     // 字一色
@@ -517,6 +835,27 @@ impl ScoringEngine {
         })
     }
 
+    // 国士無双十三面待ち（和了牌を除いた13枚が全て異なる么九牌）
+    fn is_kokushi_13_wait(hand: &Hand) -> bool {
+        let kinds: HashSet<usize> = hand.get_tiles().iter().map(|tile| tile.index()).collect();
+        kinds.len() == 13 && hand.get_tiles().iter().all(Self::is_yaochu)
+    }
+
+    // 純正九蓮宝燈（和了牌を除いた13枚が1112345678999）
+    fn is_junsei_chuuren(hand: &Hand) -> bool {
+        let tiles = hand.get_tiles();
+        if tiles.len() != 13 || !Self::check_chinitsu(tiles) {
+            return false;
+        }
+        let mut counts = [0; 9];
+        for tile in tiles {
+            if let TileType::Number { value, .. } = tile.tile_type {
+                counts[(value - 1) as usize] += 1;
+            }
+        }
+        counts == [3, 1, 1, 1, 1, 1, 1, 1, 3]
+    }
+
     // 九蓮宝燈
     fn check_chuuren(tiles: &[Tile], is_menzen: bool) -> bool {
         if !is_menzen || tiles.len() != 14 {
@@ -528,57 +867,210 @@ impl ScoringEngine {
             return false;
         }
 
-        // 1112345678999のパターン
-        // 簡易実装: 省略
-        false
+        // 1112345678999 + 同色の1枚
+        let mut counts = [0; 9];
+        for tile in tiles {
+            if let TileType::Number { value, .. } = tile.tile_type {
+                counts[(value - 1) as usize] += 1;
+            }
+        }
+        counts[0] >= 3 && counts[8] >= 3 && counts[1..8].iter().all(|&count| count >= 1)
     }
     
-    fn calculate_fu(_hand: &Hand, _winning_tile: &Tile, is_tsumo: bool) -> u32 {
-        // 簡単な符計算
-        let mut fu = 20; // 基本符
-        
-        if is_tsumo {
-            fu += 2; // ツモ符
-        }
-        
-        // 待ちや面子による符は省略（簡単な実装）
-        
-        // 10の位を切り上げ
-        ((fu + 9) / 10) * 10
+    /// 符計算（内訳を返す）
+    fn calculate_fu(shape: &WinShape, context: &WinContext, is_menzen: bool, is_pinfu: bool) -> Vec<FuItem> {
+        let item = |reason, fu| FuItem { reason, fu };
+
+        let decomposition = match shape {
+            WinShape::Chiitoitsu => return vec![item(FuReason::Chiitoitsu, 25)],
+            WinShape::Kokushi => return vec![item(FuReason::Base, 20)],
+            WinShape::Normal(decomposition) => decomposition,
+        };
+
+        let mut items = vec![item(FuReason::Base, 20)];
+
+        if is_menzen && !context.is_tsumo {
+            items.push(item(FuReason::MenzenRon, 10));
+        }
+
+        // 平和ツモは符が付かない
+        if context.is_tsumo && !is_pinfu {
+            items.push(item(FuReason::Tsumo, 2));
+        }
+
+        // 刻子・槓子
+        for set in &decomposition.sets {
+            let fu = match set.kind {
+                SetKind::Sequence => continue,
+                SetKind::OpenTriplet => 2,
+                SetKind::ConcealedTriplet => 4,
+                SetKind::Kan if set.is_open => 8,
+                SetKind::Kan => 16,
+            };
+            let fu = if Self::is_yaochu(&set.tile) { fu * 2 } else { fu };
+            items.push(item(FuReason::Set(*set), fu));
+        }
+
+        // 雀頭（役牌）
+        if let TileType::Honor(honor) = decomposition.pair.tile_type {
+            if Self::is_dragon(&decomposition.pair) {
+                items.push(item(FuReason::Pair(honor), 2));
+            }
+            if honor == context.seat_wind {
+                items.push(item(FuReason::Pair(honor), 2));
+            }
+            if honor == context.round_wind {
+                items.push(item(FuReason::Pair(honor), 2));
+            }
+        }
+
+        // 待ち
+        let wait = decomposition.wait();
+        if matches!(wait, WaitShape::Kanchan | WaitShape::Penchan | WaitShape::Tanki) {
+            items.push(item(FuReason::Wait(wait), 2));
+        }
+
+        // 喰い平和形は30符
+        if !is_menzen && items.iter().map(|i| i.fu).sum::<u32>() == 20 {
+            items.push(item(FuReason::OpenPinfu, 10));
+        }
+
+        items
     }
-    
-    fn calculate_base_points(han: u32, fu: u32) -> u32 {
+
+    /// 符の合計（七対子以外は10の位に切り上げ）
+    fn round_fu(items: &[FuItem]) -> u32 {
+        let fu: u32 = items.iter().map(|item| item.fu).sum();
+        if items.iter().any(|item| item.reason == FuReason::Chiitoitsu) {
+            fu
+        } else {
+            fu.div_ceil(10) * 10
+        }
+    }
+
+    fn calculate_base_points(han: u32, fu: u32, rules: &RuleSet) -> u32 {
         match han {
-            1..=4 => fu * (1 << (han + 2)),
+            1..=4 => {
+                let base = (fu * (1 << (han + 2))).min(2000); // 満貫で頭打ち
+                // 切り上げ満貫（4飜30符・3飜60符）
+                if rules.kiriage_mangan && base == 1920 { 2000 } else { base }
+            }
             5 => 2000,  // 満貫
             6..=7 => 3000, // 跳満
             8..=10 => 4000, // 倍満
             11..=12 => 6000, // 三倍満
-            _ => 8000, // 役満
+            _ if rules.kazoe_yakuman => 8000, // 数え役満
+            _ => 6000,
         }
     }
     
-    fn calculate_total_points(base_points: u32, is_dealer: bool, is_tsumo: bool) -> u32 {
-        if is_dealer {
-            if is_tsumo {
-                base_points * 6 // 親ツモ: 子全員からbase_points * 2
-            } else {
-                base_points * 6 // 親ロン: 放銃者からbase_points * 6
+    /// 和了点（積み棒・供託を除く、支払いごとに100点単位で切り上げた合計）
+    ///
+    /// 三人麻雀のツモはルールの支払い方式（ツモ損・北家折半）で実際に受け取る点数
+    fn calculate_total_points(base_points: u32, is_dealer: bool, is_tsumo: bool, rules: &RuleSet) -> u32 {
+        if rules.is_sanma() && is_tsumo {
+            let winner = if is_dealer { 0 } else { 1 };
+            let payment = Self::calculate_sanma_payment(base_points, winner, 0, None, 0, 0, rules.sanma_tsumo);
+            return payment.transfers.iter().map(|transfer| transfer.points).sum();
+        }
+
+        match (is_dealer, is_tsumo) {
+            (true, false) => round_up_100(base_points * 6),
+            (false, false) => round_up_100(base_points * 4),
+            (true, true) => round_up_100(base_points * 2) * 3,
+            (false, true) => round_up_100(base_points * 2) + round_up_100(base_points) * 2,
+        }
+    }
+
+    /// 和了による点数の移動を計算
+    ///
+    /// `loser` はロンの放銃者（ツモならNone）。積み棒は1本300点、供託は1本1000点。
+    pub fn calculate_payment(
+        base_points: u32,
+        winner: usize,
+        dealer: usize,
+        loser: Option<usize>,
+        honba: u32,
+        riichi_sticks: u32,
+    ) -> Payment {
+        let is_dealer = winner == dealer;
+        let mut transfers = Vec::new();
+
+        match loser {
+            Some(loser) => {
+                let multiplier = if is_dealer { 6 } else { 4 };
+                transfers.push(Transfer {
+                    from: loser,
+                    to: winner,
+                    points: round_up_100(base_points * multiplier) + honba * 300,
+                });
             }
-        } else {
-            if is_tsumo {
-                base_points * 4 // 子ツモ: 親からbase_points * 2、子からbase_points * 1ずつ
-            } else {
-                base_points * 4 // 子ロン: 放銃者からbase_points * 4
+            None => {
+                for payer in (0..4).filter(|&payer| payer != winner) {
+                    // 親の和了、または子の和了で親が払う場合は2倍
+                    let multiplier = if is_dealer || payer == dealer { 2 } else { 1 };
+                    transfers.push(Transfer {
+                        from: payer,
+                        to: winner,
+                        points: round_up_100(base_points * multiplier) + honba * 100,
+                    });
+                }
             }
         }
+
+        Payment {
+            winner,
+            transfers,
+            riichi_deposit: riichi_sticks * 1000,
+        }
+    }
+
+    /// 三人麻雀の和了による点数移動
+    ///
+    /// ロンは4人麻雀と同じ。ツモはいない北家の分をツモ損にするか、2人で折半する。
+    pub fn calculate_sanma_payment(
+        base_points: u32,
+        winner: usize,
+        dealer: usize,
+        loser: Option<usize>,
+        honba: u32,
+        riichi_sticks: u32,
+        tsumo: SanmaTsumo,
+    ) -> Payment {
+        if loser.is_some() {
+            return Self::calculate_payment(base_points, winner, dealer, loser, honba, riichi_sticks);
+        }
+
+        let is_dealer = winner == dealer;
+        // 北家が払うはずだった分（親の和了なら2倍）
+        let north_share = match tsumo {
+            SanmaTsumo::TsumoLoss => 0,
+            SanmaTsumo::NorthBisection => base_points * if is_dealer { 2 } else { 1 } / 2,
+        };
+        let transfers = (0..3)
+            .filter(|&payer| payer != winner)
+            .map(|payer| {
+                let multiplier = if is_dealer || payer == dealer { 2 } else { 1 };
+                Transfer {
+                    from: payer,
+                    to: winner,
+                    points: round_up_100(base_points * multiplier + north_share) + honba * 100,
+                }
+            })
+            .collect();
+
+        Payment {
+            winner,
+            transfers,
+            riichi_deposit: riichi_sticks * 1000,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tile::{Tile, Suit};
+    use crate::hand::{KanType, MeldType};
 
     #[test]
     fn test_tanyao_check() {
@@ -618,7 +1110,8 @@ mod tests {
         let winning_tile = crate::tile::Tile::new_honor(crate::tile::Honor::Haku);
 
         // 役牌白のみ（副露なしで門前なのでリーチ可能だが、リーチはここでは判定しない）
-        let result = ScoringEngine::calculate_score(&hand, &winning_tile, false, false);
+        let context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        let result = ScoringEngine::calculate_score(&hand, &winning_tile, &context);
 
         // 白の刻子があるので役牌が付く
         assert!(result.is_some());
@@ -626,4 +1119,501 @@ mod tests {
             assert!(scoring.han >= 1);
         }
     }
-}
\ No newline at end of file
+
+    fn hand_from(s: &str) -> Hand {
+        let mut hand = Hand::new();
+        for tile in Tile::parse_tiles(s).unwrap() {
+            hand.add_tile(tile);
+        }
+        hand
+    }
+
+    #[test]
+    fn test_best_decomposition_is_chosen() {
+        // 111222333mは順子3組より暗刻3組として数える方が高い
+        let hand = hand_from("111222333m 456p 9s");
+        let winning_tile = Tile::new_number(Suit::Sou, 9, false);
+        let context = WinContext::new(true, Honor::Nan, Honor::Ton);
+        let result = ScoringEngine::calculate_score(&hand, &winning_tile, &context).unwrap();
+        assert!(result.yaku.contains(&Yaku::Sanankou));
+    }
+
+    #[test]
+    fn test_not_winning_hand() {
+        let hand = hand_from("147m 258p 369s to na sa pe");
+        let winning_tile = Tile::new_honor(Honor::Haku);
+        let context = WinContext::new(true, Honor::Nan, Honor::Ton);
+        assert!(ScoringEngine::calculate_score(&hand, &winning_tile, &context).is_none());
+    }
+
+    fn score(tiles: &str, winning: &str, is_tsumo: bool) -> ScoringResult {
+        score_with(tiles, winning, &WinContext::new(is_tsumo, Honor::Nan, Honor::Ton))
+    }
+
+    fn score_with(tiles: &str, winning: &str, context: &WinContext) -> ScoringResult {
+        let hand = hand_from(tiles);
+        let winning_tile = Tile::from_string(winning).unwrap();
+        ScoringEngine::calculate_score(&hand, &winning_tile, context)
+            .unwrap_or_else(|| panic!("{} + {} should win", tiles, winning))
+    }
+
+    #[test]
+    fn test_pinfu() {
+        let result = score("123m 456m 789p 34s 55s", "2s", false);
+        assert!(result.yaku.contains(&Yaku::Pinfu));
+
+        // 嵌張待ちは平和にならない
+        let result = score("123m 456m 789p 35s 55s", "4s", true);
+        assert!(!result.yaku.contains(&Yaku::Pinfu));
+
+        // 辺張待ちは平和にならない
+        let result = score("123m 456m 789p 12s 55s", "3s", true);
+        assert!(!result.yaku.contains(&Yaku::Pinfu));
+
+        // 役牌の雀頭は平和にならない
+        let result = score("123m 456m 789p 34s hk hk", "2s", true);
+        assert!(!result.yaku.contains(&Yaku::Pinfu));
+
+        // 両面と嵌張の両方に取れる場合は平和を採用
+        let result = score("123m 789p 11p 34556s", "4s", false);
+        assert!(result.yaku.contains(&Yaku::Pinfu));
+    }
+
+    #[test]
+    fn test_iipeikou_and_ryanpeikou() {
+        let result = score("112233m 456p 789s 5s", "5s", false);
+        assert!(result.yaku.contains(&Yaku::Iipeikou));
+
+        // 二盃口は七対子より高く評価し、一盃口は付かない
+        let result = score("112233m 445566p 7s", "7s", false);
+        assert!(result.yaku.contains(&Yaku::Ryanpeikou));
+        assert!(!result.yaku.contains(&Yaku::Iipeikou));
+        assert!(!result.yaku.contains(&Yaku::Chiitoitsu));
+    }
+
+    #[test]
+    fn test_sanshoku_doujun() {
+        let result = score("123m 123p 123s 789m 5p", "5p", false);
+        assert!(result.yaku.contains(&Yaku::SanshokuDoujun));
+
+        let result = score("123m 123p 234s 789m 5p", "5p", true);
+        assert!(!result.yaku.contains(&Yaku::SanshokuDoujun));
+    }
+
+    #[test]
+    fn test_ittsu() {
+        let result = score("123456789m 234p 5s", "5s", false);
+        assert!(result.yaku.contains(&Yaku::Ittsu));
+        assert_eq!(result.han, 2);
+    }
+
+    #[test]
+    fn test_chanta_and_junchan() {
+        let result = score("123m 789p 111s to to to hk", "hk", false);
+        assert!(result.yaku.contains(&Yaku::Chanta));
+        assert!(!result.yaku.contains(&Yaku::Junchan));
+
+        let result = score("123m 789m 123p 999s 1p", "1p", false);
+        assert!(result.yaku.contains(&Yaku::Junchan));
+        assert!(!result.yaku.contains(&Yaku::Chanta));
+
+        // 順子がなければチャンタにならない（混老頭形）
+        let result = score("111m 999p 111s to to to hk", "hk", true);
+        assert!(!result.yaku.contains(&Yaku::Chanta));
+    }
+
+    #[test]
+    fn test_sanshoku_doukou() {
+        let result = score("222m 222p 222s 567m 9p", "9p", false);
+        assert!(result.yaku.contains(&Yaku::SanshokuDoukou));
+        assert!(result.yaku.contains(&Yaku::Sanankou));
+    }
+
+    #[test]
+    fn test_shousangen() {
+        let result = score("123m 45p hk hk hk ht ht ht cn cn", "6p", false);
+        assert!(result.yaku.contains(&Yaku::Shousangen));
+        assert!(result.yaku.contains(&Yaku::Yakuhai(Honor::Haku)));
+        assert!(result.yaku.contains(&Yaku::Yakuhai(Honor::Hatsu)));
+        assert!(!result.yaku.contains(&Yaku::Daisangen));
+    }
+
+    #[test]
+    fn test_chuuren() {
+        let result = score("1112345678999m", "5m", false);
+        assert_eq!(result.yaku, vec![Yaku::Chuuren]);
+
+        // 清一色でも形が違えば九蓮宝燈ではない
+        let result = score("2223334445566m", "6m", false);
+        assert!(!result.yaku.contains(&Yaku::Chuuren));
+        assert!(result.yaku.contains(&Yaku::Chinitsu));
+    }
+
+    #[test]
+    fn test_situational_yaku() {
+        let tiles = "123m 456m 789p 35s 55s";
+
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_riichi = true;
+        context.is_ippatsu = true;
+        let result = score_with(tiles, "4s", &context);
+        assert_eq!(result.yaku, vec![Yaku::Riichi, Yaku::Ippatsu]);
+
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_double_riichi = true;
+        context.is_last_tile = true;
+        let result = score_with(tiles, "4s", &context);
+        assert_eq!(result.yaku, vec![Yaku::DoubleRiichi, Yaku::Houtei]);
+
+        let mut context = WinContext::new(true, Honor::Nan, Honor::Ton);
+        context.is_rinshan = true;
+        context.is_last_tile = true;
+        let result = score_with(tiles, "4s", &context);
+        assert!(result.yaku.contains(&Yaku::Rinshan));
+        assert!(!result.yaku.contains(&Yaku::Haitei));
+
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_chankan = true;
+        let result = score_with(tiles, "4s", &context);
+        assert_eq!(result.yaku, vec![Yaku::Chankan]);
+    }
+
+    #[test]
+    fn test_tenhou_and_chiihou() {
+        let tiles = "123m 456m 789p 35s 55s";
+
+        let mut context = WinContext::new(true, Honor::Ton, Honor::Ton);
+        context.is_first_turn = true;
+        let result = score_with(tiles, "4s", &context);
+        assert!(result.yaku.contains(&Yaku::Tenhou));
+        assert_eq!(result.base_points, 8000);
+
+        let mut context = WinContext::new(true, Honor::Shaa, Honor::Ton);
+        context.is_first_turn = true;
+        let result = score_with(tiles, "4s", &context);
+        assert!(result.yaku.contains(&Yaku::Chiihou));
+
+        // 暗槓した後のツモは天和にならない
+        let mut hand = hand_from("1111m 456m 789p 35s 55s");
+        hand.add_meld(crate::hand::Meld {
+            meld_type: crate::hand::MeldType::Kan(crate::hand::KanType::Closed),
+            tiles: Tile::parse_tiles("1111m").unwrap(),
+            is_open: false,
+            from: None,
+            called_tile: None,
+        });
+        let mut context = WinContext::new(true, Honor::Ton, Honor::Ton);
+        context.is_first_turn = true;
+        let winning_tile = Tile::from_string("4s").unwrap();
+        let result = ScoringEngine::calculate_score(&hand, &winning_tile, &context).unwrap();
+        assert!(!result.yaku.contains(&Yaku::Tenhou));
+    }
+
+    #[test]
+    fn test_wind_yakuhai() {
+        let tiles = "123m 456m 789p 5s na na na";
+
+        // 自風の南
+        let result = score_with(tiles, "5s", &WinContext::new(false, Honor::Nan, Honor::Ton));
+        assert_eq!(result.yaku, vec![Yaku::Yakuhai(Honor::Nan)]);
+
+        // 南場の南家は連風牌で2飜
+        let result = score_with(tiles, "5s", &WinContext::new(false, Honor::Nan, Honor::Nan));
+        assert_eq!(result.han, 2);
+
+        // 客風は役にならない
+        let hand = hand_from(tiles);
+        let winning_tile = Tile::from_string("5s").unwrap();
+        let context = WinContext::new(false, Honor::Shaa, Honor::Ton);
+        assert!(ScoringEngine::calculate_score(&hand, &winning_tile, &context).is_none());
+
+        // 自風の雀頭は平和にならない
+        let context = WinContext::new(true, Honor::Shaa, Honor::Ton);
+        let result = score_with("123m 456m 789p 34s sa sa", "2s", &context);
+        assert!(!result.yaku.contains(&Yaku::Pinfu));
+    }
+
+    fn fu_of(result: &ScoringResult) -> u32 {
+        result.fu_items.iter().map(|item| item.fu).sum()
+    }
+
+    #[test]
+    fn test_fu_pinfu() {
+        // 平和ロン 20+10
+        let result = score("123m 456m 789p 34s 55s", "2s", false);
+        assert_eq!(result.fu, 30);
+
+        // 平和ツモ 20符
+        let result = score("123m 456m 789p 34s 55s", "2s", true);
+        assert!(result.yaku.contains(&Yaku::Pinfu));
+        assert_eq!(result.fu, 20);
+        assert!(!result.fu_items.iter().any(|item| item.reason == FuReason::Tsumo));
+    }
+
+    #[test]
+    fn test_fu_sets_and_waits() {
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_riichi = true;
+
+        // 暗刻中張牌4 + 暗刻么九牌8 + 嵌張2 + 門前ロン10 + 副底20 = 44 → 50符
+        let result = score_with("222m 999p 456s 13m 77p", "2m", &context);
+        assert_eq!(fu_of(&result), 44);
+        assert_eq!(result.fu, 50);
+        assert!(result.fu_items.contains(&FuItem { reason: FuReason::Wait(WaitShape::Kanchan), fu: 2 }));
+
+        // シャンポンのロンは明刻扱い: 明刻么九牌4 + 暗刻中張牌4
+        let result = score_with("123m 456p 99s 444m 22p", "9s", &context);
+        assert!(result.fu_items.iter().any(|item| {
+            matches!(item.reason, FuReason::Set(set) if set.kind == SetKind::OpenTriplet) && item.fu == 4
+        }));
+    }
+
+    #[test]
+    fn test_fu_pair() {
+        // 連風牌の雀頭は4符、単騎待ち2符
+        let mut context = WinContext::new(false, Honor::Ton, Honor::Ton);
+        context.is_riichi = true;
+        let result = score_with("234m 456m 789p 234s to", "to", &context);
+        let pair_fu: u32 = result
+            .fu_items
+            .iter()
+            .filter(|item| matches!(item.reason, FuReason::Pair(_)))
+            .map(|item| item.fu)
+            .sum();
+        assert_eq!(pair_fu, 4);
+        assert!(result.fu_items.contains(&FuItem { reason: FuReason::Wait(WaitShape::Tanki), fu: 2 }));
+    }
+
+    #[test]
+    fn test_fu_chiitoitsu_and_open_pinfu() {
+        let result = score("11m 22m 33p 44p 55s 66s 7s", "7s", false);
+        assert!(result.yaku.contains(&Yaku::Chiitoitsu));
+        assert_eq!(result.fu, 25);
+
+        // 喰いタンの平和形ロンは30符
+        let mut hand = hand_from("234m 456m 678p 34s 55s");
+        let chi = Tile::parse_tiles("234m").unwrap();
+        hand.add_meld(crate::hand::Meld {
+            meld_type: crate::hand::MeldType::Chi,
+            tiles: chi,
+            is_open: true,
+            from: Some(3),
+            called_tile: None,
+        });
+        let winning_tile = Tile::from_string("2s").unwrap();
+        let context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        let result = ScoringEngine::calculate_score(&hand, &winning_tile, &context).unwrap();
+        assert_eq!(result.fu, 30);
+        assert!(result.fu_items.iter().any(|item| item.reason == FuReason::OpenPinfu));
+    }
+
+    #[test]
+    fn test_mangan_cap() {
+        // 4飜40符は満貫
+        let rules = RuleSet::default();
+        assert_eq!(ScoringEngine::calculate_base_points(4, 40, &rules), 2000);
+        assert_eq!(ScoringEngine::calculate_base_points(3, 60, &rules), 1920);
+    }
+
+    #[test]
+    fn test_dora() {
+        let tiles = "123m 456m 789p 3s 5sr 55s";
+        let winning_tile = Tile::from_string("4s").unwrap();
+
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_riichi = true;
+        context.dora_indicators = Tile::parse_tiles("9p 4s").unwrap();
+        context.ura_dora_indicators = Tile::parse_tiles("3m").unwrap();
+        let result = score_with(tiles, "4s", &context);
+        // 1p(0) + 5s(3) / 裏4m(1) / 赤5s(1)
+        assert_eq!((result.dora, result.ura_dora, result.aka_dora), (3, 1, 1));
+        assert_eq!(result.han, 1 + 5);
+        assert_eq!(result.yaku, vec![Yaku::Riichi]);
+
+        // ドラだけでは和了れない
+        context.is_riichi = false;
+        assert!(ScoringEngine::calculate_score(&hand_from(tiles), &winning_tile, &context).is_none());
+    }
+
+    #[test]
+    fn test_total_points() {
+        let rules = RuleSet::default();
+        // 1飜30符: 子ロン1000、子ツモ300/500
+        assert_eq!(ScoringEngine::calculate_total_points(240, false, false, &rules), 1000);
+        assert_eq!(ScoringEngine::calculate_total_points(240, false, true, &rules), 1100);
+        // 4飜30符: 親ロン11600、子ロン7700、子ツモ2000/3900
+        assert_eq!(ScoringEngine::calculate_total_points(1920, true, false, &rules), 11600);
+        assert_eq!(ScoringEngine::calculate_total_points(1920, false, false, &rules), 7700);
+        assert_eq!(ScoringEngine::calculate_total_points(1920, false, true, &rules), 7900);
+        assert_eq!(ScoringEngine::calculate_total_points(1920, true, true, &rules), 11700);
+
+        // 三人麻雀のツモ損は北家の分が減り、北家折半は残り2人で負担
+        let mut rules = RuleSet::sanma();
+        rules.sanma_tsumo = SanmaTsumo::TsumoLoss;
+        assert_eq!(ScoringEngine::calculate_total_points(2000, false, true, &rules), 6000);
+        assert_eq!(ScoringEngine::calculate_total_points(2000, true, true, &rules), 8000);
+        assert_eq!(ScoringEngine::calculate_total_points(2000, false, false, &rules), 8000);
+        rules.sanma_tsumo = SanmaTsumo::NorthBisection;
+        assert_eq!(ScoringEngine::calculate_total_points(2000, false, true, &rules), 8000);
+        assert_eq!(ScoringEngine::calculate_total_points(2000, true, true, &rules), 12000);
+    }
+
+    #[test]
+    fn test_payment_ron() {
+        // 子の満貫ロン、1本場、供託2本
+        let payment = ScoringEngine::calculate_payment(2000, 1, 0, Some(3), 1, 2);
+        assert_eq!(payment.transfers, vec![Transfer { from: 3, to: 1, points: 8300 }]);
+        assert_eq!(payment.delta(1), 10300);
+        assert_eq!(payment.delta(3), -8300);
+        assert_eq!(payment.delta(0), 0);
+    }
+
+    #[test]
+    fn test_payment_tsumo() {
+        // 子の1飜30符ツモ: 親500、子300
+        let payment = ScoringEngine::calculate_payment(240, 2, 0, None, 0, 0);
+        assert_eq!(payment.delta(0), -500);
+        assert_eq!(payment.delta(1), -300);
+        assert_eq!(payment.delta(3), -300);
+        assert_eq!(payment.delta(2), 1100);
+
+        // 親の4飜30符ツモ、2本場: 3900+200オール
+        let payment = ScoringEngine::calculate_payment(1920, 0, 0, None, 2, 0);
+        for payer in 1..4 {
+            assert_eq!(payment.delta(payer), -4100);
+        }
+        assert_eq!(payment.delta(0), 12300);
+    }
+
+    #[test]
+    fn test_sankantsu_and_suukantsu() {
+        let kan = |hand: &mut Hand, s: &str, kan_type: KanType| {
+            hand.add_meld(crate::hand::Meld {
+                meld_type: MeldType::Kan(kan_type),
+                tiles: Tile::parse_tiles(s).unwrap(),
+                is_open: kan_type != KanType::Closed,
+                from: None,
+                called_tile: None,
+            });
+        };
+        let context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        let winning_tile = Tile::new_number(Suit::Pin, 7, false);
+
+        let mut hand = hand_from("345s 7p");
+        kan(&mut hand, "1111m", KanType::Closed);
+        kan(&mut hand, "2222p", KanType::Open);
+        kan(&mut hand, "9999s", KanType::Added);
+        let result = ScoringEngine::calculate_score(&hand, &winning_tile, &context).unwrap();
+        assert_eq!(result.yaku, vec![Yaku::Sankantsu]);
+        // 暗槓(么九)32符 + 明槓8符 + 明槓(么九)16符
+        assert!(result.fu_items.iter().any(|item| item.fu == 32));
+
+        let mut hand = hand_from("7p");
+        kan(&mut hand, "1111m", KanType::Closed);
+        kan(&mut hand, "2222p", KanType::Open);
+        kan(&mut hand, "9999s", KanType::Added);
+        kan(&mut hand, "4444s", KanType::Open);
+        let result = ScoringEngine::calculate_score(&hand, &winning_tile, &context).unwrap();
+        assert!(result.yaku.contains(&Yaku::Suukantsu));
+    }
+
+    #[test]
+    fn test_rule_options() {
+        let mut rules = RuleSet::default();
+        assert_eq!(ScoringEngine::calculate_base_points(13, 30, &rules), 8000);
+        rules.kiriage_mangan = true;
+        rules.kazoe_yakuman = false;
+        assert_eq!(ScoringEngine::calculate_base_points(4, 30, &rules), 2000);
+        assert_eq!(ScoringEngine::calculate_base_points(3, 60, &rules), 2000);
+        assert_eq!(ScoringEngine::calculate_base_points(13, 30, &rules), 6000);
+
+        // 喰いタンなしなら副露した断么九だけでは和了れない
+        let mut hand = hand_from("234m 456m 678p 34s 55s");
+        hand.add_meld(crate::hand::Meld {
+            meld_type: MeldType::Chi,
+            tiles: Tile::parse_tiles("234m").unwrap(),
+            is_open: true,
+            from: Some(3),
+            called_tile: None,
+        });
+        let winning_tile = Tile::from_string("2s").unwrap();
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        assert!(ScoringEngine::calculate_score(&hand, &winning_tile, &context).is_some());
+        context.rules.kuitan = false;
+        assert!(ScoringEngine::calculate_score(&hand, &winning_tile, &context).is_none());
+    }
+
+    #[test]
+    fn test_multiple_and_double_yakuman() {
+        // 四暗刻単騎
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        assert_eq!(score_with("111m 333p 555s 777s 9p", "9p", &context).base_points, 8000);
+        context.rules.double_yakuman = true;
+        assert_eq!(score_with("111m 333p 555s 777s 9p", "9p", &context).base_points, 16000);
+        // 単騎でなければシングル
+        assert_eq!(score_with("111m 333p 555s 77s 99p", "7s", &WinContext::new(true, Honor::Nan, Honor::Ton)).base_points, 8000);
+
+        // 字一色・大三元・四暗刻単騎の複合
+        let tiles = "to to to hk hk hk ht ht ht cn cn cn na";
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        let result = score_with(tiles, "na", &context);
+        assert_eq!(result.base_points, 3 * 8000);
+        assert_eq!(result.han, 39);
+        context.rules = RuleSet::m_league();
+        assert_eq!(score_with(tiles, "na", &context).base_points, 8000);
+    }
+
+    #[test]
+    fn test_sanma_payment() {
+        use crate::rules::SanmaTsumo;
+
+        // 子の満貫ツモ: ツモ損なら親4000・子2000
+        let payment = ScoringEngine::calculate_sanma_payment(2000, 1, 0, None, 0, 0, SanmaTsumo::TsumoLoss);
+        assert_eq!(payment.delta(0), -4000);
+        assert_eq!(payment.delta(2), -2000);
+        assert_eq!(payment.delta(1), 6000);
+
+        // 北家折半なら北家の2000を半分ずつ
+        let payment = ScoringEngine::calculate_sanma_payment(2000, 1, 0, None, 1, 0, SanmaTsumo::NorthBisection);
+        assert_eq!(payment.delta(0), -5100);
+        assert_eq!(payment.delta(2), -3100);
+
+        // 親の満貫ツモ折半: 4000+2000オール
+        let payment = ScoringEngine::calculate_sanma_payment(2000, 0, 0, None, 0, 0, SanmaTsumo::NorthBisection);
+        assert_eq!(payment.delta(0), 12000);
+
+        // ロンは4人麻雀と同じ
+        let payment = ScoringEngine::calculate_sanma_payment(2000, 1, 0, Some(2), 0, 0, SanmaTsumo::TsumoLoss);
+        assert_eq!(payment.delta(2), -8000);
+    }
+
+    #[test]
+    fn test_kita_counts_as_dora() {
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_riichi = true;
+        context.kita = 2;
+        let result = score_with("123m 456m 789p 35s 99p", "4s", &context);
+        assert_eq!(result.kita, 2);
+
+        // 表示牌が西なら北1枚で2飜
+        context.dora_indicators = vec![Tile::new_honor(Honor::Shaa)];
+        let result = score_with("123m 456m 789p 35s 99p", "4s", &context);
+        assert_eq!(result.kita, 4);
+        assert_eq!(result.yaku, vec![Yaku::Riichi]);
+    }
+
+    #[test]
+    fn test_sanma_dora_indicator_wraps_to_nine_man() {
+        let mut context = WinContext::new(false, Honor::Nan, Honor::Ton);
+        context.is_riichi = true;
+        context.rules = RuleSet::sanma();
+        context.dora_indicators = vec![Tile::new_number(Suit::Man, 1, false)];
+        let result = score_with("123p 456p 789p 35s 99m", "4s", &context);
+        assert_eq!(result.dora, 2);
+
+        // 四人麻雀なら1m表示のドラは2m
+        context.rules = RuleSet::default();
+        let result = score_with("123p 456p 789p 35s 99m", "4s", &context);
+        assert_eq!(result.dora, 0);
+    }
+}
@@ -0,0 +1,117 @@
+//! 終局時の順位と最終ポイント（ウマ・オカ）
+
+use crate::player::Player;
+use crate::rules::RuleSet;
+use serde::{Deserialize, Serialize};
+
+/// 1人分の最終成績
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub player: usize, // 起家からの席順
+    pub name: String,
+    pub rank: usize,   // 着順（1〜）
+    pub score: i32,    // 最終持ち点（トップは供託を含む）
+    pub points: f64,   // 返し点との差にウマ・オカを加えたポイント（千点単位）
+}
+
+/// 対局の最終結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub standings: Vec<Standing>,    // 着順に並ぶ
+    pub leftover_riichi_sticks: u32, // 終局時に残っていた供託（トップが受け取る）
+}
+
+impl MatchResult {
+    /// 持ち点で順位を付ける（同点は起家に近い方が上位）
+    pub fn new(players: &[Player], riichi_sticks: u32, rules: &RuleSet) -> Self {
+        let mut order: Vec<&Player> = players.iter().collect();
+        order.sort_by_key(|player| (-player.score, player.id));
+
+        let standings = order
+            .into_iter()
+            .enumerate()
+            .map(|(i, player)| {
+                let mut score = player.score;
+                let mut bonus = 0;
+                if i == 0 {
+                    score += riichi_sticks as i32 * 1000;
+                    bonus = rules.oka;
+                }
+                let uma = rules.uma.get(i).copied().unwrap_or(0);
+                Standing {
+                    player: player.id,
+                    name: player.name.clone(),
+                    rank: i + 1,
+                    score,
+                    points: (score - rules.target_score + bonus) as f64 / 1000.0 + uma as f64,
+                }
+            })
+            .collect();
+
+        Self {
+            standings,
+            leftover_riichi_sticks: riichi_sticks,
+        }
+    }
+
+    /// 指定プレイヤーの成績
+    pub fn standing(&self, player: usize) -> Option<&Standing> {
+        self.standings.iter().find(|standing| standing.player == player)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(scores: [i32; 4]) -> Vec<Player> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(i, &score)| {
+                let mut player = Player::new(i, format!("P{}", i + 1));
+                player.score = score;
+                player
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_uma_oka_and_leftover_sticks() {
+        let rules = RuleSet::default(); // 25000点持ち30000点返し、ウマ10-20
+        let result = MatchResult::new(&players([18000, 42000, 31000, 7000]), 2, &rules);
+
+        let ranks: Vec<usize> = result.standings.iter().map(|s| s.player).collect();
+        assert_eq!(ranks, vec![1, 2, 0, 3]);
+
+        // トップは供託2本とオカ20000点を受け取る
+        let top = result.standing(1).unwrap();
+        assert_eq!(top.score, 44000);
+        assert_eq!(top.points, 14.0 + 20.0 + 20.0);
+        assert_eq!(result.standing(2).unwrap().points, 1.0 + 10.0);
+        assert_eq!(result.standing(0).unwrap().points, -12.0 - 10.0);
+        assert_eq!(result.standing(3).unwrap().points, -23.0 - 20.0);
+
+        // 供託もトップに渡るのでポイントの合計は0
+        let total: f64 = result.standings.iter().map(|s| s.points).sum();
+        assert_eq!(total, 0.0);
+    }
+
+    #[test]
+    fn test_ties_broken_by_seat() {
+        let result = MatchResult::new(&players([25000, 25000, 25000, 25000]), 0, &RuleSet::default());
+        let ranks: Vec<(usize, usize)> = result.standings.iter().map(|s| (s.player, s.rank)).collect();
+        assert_eq!(ranks, vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+
+        let json = result.to_json().unwrap();
+        assert_eq!(MatchResult::from_json(&json).unwrap(), result);
+    }
+}
@@ -1,11 +1,17 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 牌の種類数（萬子9・筒子9・索子9・字牌7）
+pub const TILE_KINDS: usize = 34;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
     Man,    // 萬子 (m)
     Pin,    // 筒子 (p)
     Sou,    // 索子 (s)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Honor {
     Ton,    // 東 (to)
     Nan,    // 南 (na)
@@ -16,13 +22,15 @@ pub enum Honor {
     Chun,   // 中 (cn)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TileType {
     Number { suit: Suit, value: u8 },
     Honor(Honor),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// JSONでは "5pr" のような文字列表記になる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
 pub struct Tile {
     pub tile_type: TileType,
     pub is_red: bool,  // 赤ドラ用
@@ -30,7 +38,7 @@ pub struct Tile {
 
 impl Tile {
     pub fn new_number(suit: Suit, value: u8, is_red: bool) -> Self {
-        assert!(value >= 1 && value <= 9, "Invalid tile value: {}", value);
+        assert!((1..=9).contains(&value), "Invalid tile value: {}", value);
         Self {
             tile_type: TileType::Number { suit, value },
             is_red,
@@ -44,34 +52,79 @@ impl Tile {
         }
     }
 
-    pub fn to_string(&self) -> String {
+    /// 牌種のインデックス（0-8: 萬子, 9-17: 筒子, 18-26: 索子, 27-33: 東南西北白発中）
+    ///
+    /// 赤ドラは通常牌と同じインデックスになる
+    pub fn index(&self) -> usize {
         match self.tile_type {
             TileType::Number { suit, value } => {
-                let suit_char = match suit {
-                    Suit::Man => "m",
-                    Suit::Pin => "p", 
-                    Suit::Sou => "s",
+                let base = match suit {
+                    Suit::Man => 0,
+                    Suit::Pin => 9,
+                    Suit::Sou => 18,
                 };
-                if self.is_red {
-                    format!("{}{}r", value, suit_char)
-                } else {
-                    format!("{}{}", value, suit_char)
-                }
+                base + (value - 1) as usize
             }
             TileType::Honor(honor) => {
-                match honor {
-                    Honor::Ton => "to".to_string(),
-                    Honor::Nan => "na".to_string(),
-                    Honor::Shaa => "sa".to_string(),
-                    Honor::Pei => "pe".to_string(),
-                    Honor::Haku => "hk".to_string(),
-                    Honor::Hatsu => "ht".to_string(),
-                    Honor::Chun => "cn".to_string(),
+                27 + match honor {
+                    Honor::Ton => 0,
+                    Honor::Nan => 1,
+                    Honor::Shaa => 2,
+                    Honor::Pei => 3,
+                    Honor::Haku => 4,
+                    Honor::Hatsu => 5,
+                    Honor::Chun => 6,
                 }
             }
         }
     }
 
+    /// インデックスから牌を作成（赤ドラではない通常牌）
+    pub fn from_index(index: usize) -> Self {
+        assert!(index < TILE_KINDS, "Invalid tile index: {}", index);
+        match index {
+            0..=8 => Self::new_number(Suit::Man, index as u8 + 1, false),
+            9..=17 => Self::new_number(Suit::Pin, (index - 9) as u8 + 1, false),
+            18..=26 => Self::new_number(Suit::Sou, (index - 18) as u8 + 1, false),
+            _ => Self::new_honor(match index - 27 {
+                0 => Honor::Ton,
+                1 => Honor::Nan,
+                2 => Honor::Shaa,
+                3 => Honor::Pei,
+                4 => Honor::Haku,
+                5 => Honor::Hatsu,
+                _ => Honor::Chun,
+            }),
+        }
+    }
+
+    /// ドラ表示牌が示すドラ（9→1、北→東、中→白で循環）
+    pub fn indicated_dora(&self) -> Self {
+        let index = self.index();
+        let next = match index {
+            0..=26 => index / 9 * 9 + (index % 9 + 1) % 9,
+            27..=30 => 27 + (index - 27 + 1) % 4,
+            _ => 31 + (index - 31 + 1) % 3,
+        };
+        Self::from_index(next)
+    }
+
+    /// 三人麻雀のドラ（萬子の2〜8を抜くので1m表示なら9m）
+    pub fn indicated_dora_sanma(&self) -> Self {
+        match self.tile_type {
+            TileType::Number { suit: Suit::Man, value: 1 } => Self::new_number(Suit::Man, 9, false),
+            _ => self.indicated_dora(),
+        }
+    }
+
+    /// 赤ドラ情報を落とした通常牌を返す
+    pub fn normalized(&self) -> Self {
+        Self {
+            tile_type: self.tile_type,
+            is_red: false,
+        }
+    }
+
     pub fn from_string(s: &str) -> Option<Self> {
         if s.len() < 2 {
             return None;
@@ -98,7 +151,7 @@ impl Tile {
         }
 
         let value = chars[0].to_digit(10)? as u8;
-        if value < 1 || value > 9 {
+        if !(1..=9).contains(&value) {
             return None;
         }
 
@@ -114,6 +167,74 @@ impl Tile {
 
         Some(Self::new_number(suit, value, is_red))
     }
+
+    /// 空白区切りの牌列を解析（例: "123m 456p 5sr to to"）
+    ///
+    /// 数牌は "123m" のように数字を続けてまとめて書ける
+    pub fn parse_tiles(s: &str) -> Option<Vec<Self>> {
+        let mut tiles = Vec::new();
+        for token in s.split_whitespace() {
+            if let Some(tile) = Self::from_string(token) {
+                tiles.push(tile);
+                continue;
+            }
+
+            let suit_pos = token.char_indices().last()?.0;
+            let (digits, suit) = token.split_at(suit_pos);
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            for digit in digits.chars() {
+                tiles.push(Self::from_string(&format!("{}{}", digit, suit))?);
+            }
+        }
+        Some(tiles)
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tile_type {
+            TileType::Number { suit, value } => {
+                let suit_char = match suit {
+                    Suit::Man => "m",
+                    Suit::Pin => "p",
+                    Suit::Sou => "s",
+                };
+                if self.is_red {
+                    write!(f, "{}{}r", value, suit_char)
+                } else {
+                    write!(f, "{}{}", value, suit_char)
+                }
+            }
+            TileType::Honor(honor) => {
+                let s = match honor {
+                    Honor::Ton => "to",
+                    Honor::Nan => "na",
+                    Honor::Shaa => "sa",
+                    Honor::Pei => "pe",
+                    Honor::Haku => "hk",
+                    Honor::Hatsu => "ht",
+                    Honor::Chun => "cn",
+                };
+                write!(f, "{}", s)
+            }
+        }
+    }
+}
+
+impl From<Tile> for String {
+    fn from(tile: Tile) -> Self {
+        tile.to_string()
+    }
+}
+
+impl TryFrom<String> for Tile {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Tile::from_string(&s).ok_or_else(|| format!("Invalid tile: {}", s))
+    }
 }
 
 #[cfg(test)]
@@ -139,4 +260,48 @@ mod tests {
         assert_eq!(Tile::from_string("to").unwrap().to_string(), "to");
         assert!(Tile::from_string("invalid").is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_tiles() {
+        let tiles = Tile::parse_tiles("123m 5pr to").unwrap();
+        let strs: Vec<String> = tiles.iter().map(|t| t.to_string()).collect();
+        assert_eq!(strs, vec!["1m", "2m", "3m", "5pr", "to"]);
+        assert!(Tile::parse_tiles("12x").is_none());
+    }
+
+    #[test]
+    fn test_tile_index_roundtrip() {
+        for index in 0..TILE_KINDS {
+            assert_eq!(Tile::from_index(index).index(), index);
+        }
+        assert_eq!(Tile::from_string("5pr").unwrap().index(), 13);
+        assert_eq!(Tile::from_string("cn").unwrap().index(), 33);
+    }
+
+    #[test]
+    fn test_indicated_dora() {
+        let dora = |s: &str| Tile::from_string(s).unwrap().indicated_dora().to_string();
+        assert_eq!(dora("1m"), "2m");
+        assert_eq!(dora("9p"), "1p");
+        assert_eq!(dora("5sr"), "6s");
+        assert_eq!(dora("pe"), "to");
+        assert_eq!(dora("sa"), "pe");
+        assert_eq!(dora("cn"), "hk");
+        assert_eq!(dora("hk"), "ht");
+
+        let sanma = |s: &str| Tile::from_string(s).unwrap().indicated_dora_sanma().to_string();
+        assert_eq!(sanma("1m"), "9m");
+        assert_eq!(sanma("9m"), "1m");
+        assert_eq!(sanma("9p"), "1p");
+        assert_eq!(sanma("pe"), "to");
+    }
+
+    #[test]
+    fn test_tile_serde() {
+        let tiles = Tile::parse_tiles("1m 5pr cn").unwrap();
+        let json = serde_json::to_string(&tiles).unwrap();
+        assert_eq!(json, r#"["1m","5pr","cn"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Tile>>(&json).unwrap(), tiles);
+        assert!(serde_json::from_str::<Tile>(r#""0m""#).is_err());
+    }
+}
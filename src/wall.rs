@@ -0,0 +1,157 @@
+//! 山牌（ツモ山と王牌）
+
+use crate::tile::Tile;
+
+/// 王牌の枚数
+pub const DEAD_WALL_SIZE: usize = 14;
+/// 嶺上牌の枚数
+pub const RINSHAN_COUNT: usize = 4;
+/// 三人麻雀の嶺上牌の枚数（カン4回と北抜き4回）
+pub const SANMA_RINSHAN_COUNT: usize = 8;
+/// ドラ表示牌（裏ドラ表示牌も同数）の最大枚数
+pub const MAX_DORA_INDICATORS: usize = 5;
+
+/// 山牌
+///
+/// 王牌は先頭から嶺上牌4枚・ドラ表示牌5枚・裏ドラ表示牌5枚の順に並ぶ。
+/// 嶺上牌を取るたびにツモ山の最後の1枚（海底側）が王牌に補充される。
+#[derive(Debug, Clone, Default)]
+pub struct Wall {
+    live: Vec<Tile>, // ツモ山（末尾からツモる）
+    dead: Vec<Tile>, // 王牌
+    rinshan_count: usize,
+    rinshan_drawn: usize,
+    revealed: usize, // めくったドラ表示牌の枚数
+}
+
+impl Wall {
+    /// 並べた牌から山を作る（末尾14枚が王牌）
+    pub fn new(tiles: Vec<Tile>) -> Self {
+        Self::with_rinshan(tiles, RINSHAN_COUNT)
+    }
+
+    /// 嶺上牌の枚数を指定して山を作る（王牌は嶺上牌とドラ・裏ドラ表示牌各5枚）
+    pub fn with_rinshan(mut tiles: Vec<Tile>, rinshan_count: usize) -> Self {
+        let dead_size = rinshan_count + MAX_DORA_INDICATORS * 2;
+        let dead = tiles.split_off(tiles.len().saturating_sub(dead_size));
+        Self {
+            live: tiles,
+            dead,
+            rinshan_count,
+            rinshan_drawn: 0,
+            revealed: 0,
+        }
+    }
+
+    /// ツモ山から1枚ツモる
+    pub fn draw(&mut self) -> Option<Tile> {
+        self.live.pop()
+    }
+
+    /// 嶺上牌をツモる（ツモ山の海底側から1枚補充）
+    pub fn draw_rinshan(&mut self) -> Option<Tile> {
+        if self.rinshan_drawn >= self.rinshan_count {
+            return None;
+        }
+        let tile = *self.dead.get(self.rinshan_drawn)?;
+        self.rinshan_drawn += 1;
+        if !self.live.is_empty() {
+            let replenished = self.live.remove(0);
+            self.dead.push(replenished);
+        }
+        Some(tile)
+    }
+
+    /// 次のドラ表示牌をめくる
+    pub fn reveal_dora(&mut self) -> Option<Tile> {
+        if self.revealed >= MAX_DORA_INDICATORS {
+            return None;
+        }
+        let indicator = *self.dead.get(self.rinshan_count + self.revealed)?;
+        self.revealed += 1;
+        Some(indicator)
+    }
+
+    /// めくられたドラ表示牌
+    pub fn dora_indicators(&self) -> &[Tile] {
+        let start = self.rinshan_count.min(self.dead.len());
+        let end = (self.rinshan_count + self.revealed).min(self.dead.len());
+        &self.dead[start..end]
+    }
+
+    /// めくられたドラ表示牌に対応する裏ドラ表示牌
+    pub fn ura_dora_indicators(&self) -> &[Tile] {
+        let start = (self.rinshan_count + MAX_DORA_INDICATORS).min(self.dead.len());
+        let end = (start + self.revealed).min(self.dead.len());
+        &self.dead[start..end]
+    }
+
+    /// ツモ山の残り枚数
+    pub fn remaining(&self) -> usize {
+        self.live.len()
+    }
+
+    /// ツモ山が尽きたか
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    /// 残りの嶺上牌の枚数
+    pub fn rinshan_remaining(&self) -> usize {
+        self.rinshan_count - self.rinshan_drawn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered_wall() -> (Vec<Tile>, Wall) {
+        let tiles: Vec<Tile> = (0..136).map(|i| Tile::from_index(i / 4)).collect();
+        (tiles.clone(), Wall::new(tiles))
+    }
+
+    #[test]
+    fn test_dead_wall_layout() {
+        let (tiles, mut wall) = numbered_wall();
+        assert_eq!(wall.remaining(), 122);
+        assert!(wall.dora_indicators().is_empty());
+
+        assert_eq!(wall.reveal_dora(), Some(tiles[122 + 4]));
+        assert_eq!(wall.dora_indicators(), &tiles[126..127]);
+        assert_eq!(wall.ura_dora_indicators(), &tiles[131..132]);
+
+        for _ in 0..4 {
+            wall.reveal_dora();
+        }
+        assert_eq!(wall.reveal_dora(), None);
+        assert_eq!(wall.dora_indicators().len(), 5);
+        assert_eq!(wall.ura_dora_indicators().len(), 5);
+    }
+
+    #[test]
+    fn test_rinshan_shrinks_live_wall() {
+        let (tiles, mut wall) = numbered_wall();
+        assert_eq!(wall.draw(), Some(tiles[121]));
+
+        for i in 0..4 {
+            assert_eq!(wall.draw_rinshan(), Some(tiles[122 + i]));
+        }
+        assert_eq!(wall.draw_rinshan(), None);
+        assert_eq!(wall.rinshan_remaining(), 0);
+        assert_eq!(wall.remaining(), 121 - 4);
+
+        // 補充があってもドラ表示牌の位置は変わらない
+        wall.reveal_dora();
+        assert_eq!(wall.dora_indicators(), &tiles[126..127]);
+    }
+
+    #[test]
+    fn test_sanma_wall() {
+        let tiles: Vec<Tile> = (0..108).map(|i| Tile::from_index(i / 4)).collect();
+        let mut wall = Wall::with_rinshan(tiles.clone(), SANMA_RINSHAN_COUNT);
+        assert_eq!(wall.remaining(), 108 - 18);
+        assert_eq!(wall.rinshan_remaining(), 8);
+        assert_eq!(wall.reveal_dora(), Some(tiles[90 + 8]));
+    }
+}
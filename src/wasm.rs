@@ -4,13 +4,14 @@
 use wasm_bindgen::prelude::*;
 
 #[cfg(feature = "wasm")]
-use crate::{Game, Tile, Hand, Player, AiEngine, AiLevel};
+use crate::{Game, Claim, Tile, Hand, Player, AiEngine, AiLevel, RuleSet};
 
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 pub struct WasmGame {
     game: Game,
     human_player_index: Option<usize>, // ハイブリッドモード用：人間プレイヤーの位置（0-3）
+    ais: Vec<AiEngine>,                // CPUごとのAI（対局のシード値から決まる）
 }
 
 #[cfg(feature = "wasm")]
@@ -22,10 +23,38 @@ impl WasmGame {
         #[cfg(feature = "console_error_panic_hook")]
         console_error_panic_hook::set_once();
 
-        Self {
-            game: Game::new(player_names),
-            human_player_index: None, // 通常モードは全員人間
-        }
+        Self::from_game(Game::new(player_names, RuleSet::default()), None) // 通常モードは全員人間
+    }
+
+    /// ルールを指定してゲームを作成（ルールはJSON、省略した項目は既定値）
+    #[wasm_bindgen(js_name = withRules)]
+    pub fn with_rules(player_names: Vec<String>, rules_json: &str) -> Result<WasmGame, JsValue> {
+        let rules: RuleSet = serde_json::from_str(rules_json)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(Self::from_game(Game::new(player_names, rules), None))
+    }
+
+    /// シード値とルールを指定してゲームを作成（ネイティブと同じ山が再現される）
+    #[wasm_bindgen(js_name = withSeed)]
+    pub fn with_seed(player_names: Vec<String>, rules_json: &str, seed: u64) -> Result<WasmGame, JsValue> {
+        let rules: RuleSet = serde_json::from_str(rules_json)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        Ok(Self::from_game(Game::with_seed(player_names, rules, seed), None))
+    }
+
+    /// 対局のシード値
+    #[wasm_bindgen(js_name = getSeed)]
+    pub fn get_seed(&self) -> u64 {
+        self.game.seed()
+    }
+
+    fn from_game(game: Game, human_player_index: Option<usize>) -> Self {
+        let ais = (0..game.player_count())
+            .map(|i| AiEngine::with_seed(AiLevel::Intermediate, game.ai_seed(i)))
+            .collect();
+        Self { game, human_player_index, ais }
     }
 
     /// ハイブリッドゲームを作成（1人間 + 3CPU）
@@ -46,19 +75,14 @@ impl WasmGame {
         let position = human_position % 4;
         names[position] = human_name;
 
-        let game = Game::new(names);
-
-        Self {
-            game,
-            human_player_index: Some(position),
-        }
+        Self::from_game(Game::new(names, RuleSet::default()), Some(position))
     }
 
     /// 現在のプレイヤーが人間かどうか
     #[wasm_bindgen(js_name = isCurrentPlayerHuman)]
     pub fn is_current_player_human(&self) -> bool {
         match self.human_player_index {
-            Some(human_idx) => self.game.current_player == human_idx,
+            Some(human_idx) => self.game.current_player() == human_idx,
             None => true, // ハイブリッドモードでない場合は全員人間扱い
         }
     }
@@ -73,7 +97,9 @@ impl WasmGame {
     #[wasm_bindgen(js_name = discardTile)]
     pub fn discard_tile(&mut self, tile_str: &str) -> bool {
         if let Some(tile) = Tile::from_string(tile_str) {
-            self.game.discard_tile(tile)
+            let discarded = self.game.discard_tile(tile);
+            self.auto_respond_cpus();
+            discarded
         } else {
             false
         }
@@ -97,22 +123,66 @@ impl WasmGame {
         self.game.get_current_player().hand.shanten()
     }
 
+    /// プレイヤーの待ち牌を取得（テンパイでなければ空文字列）
+    #[wasm_bindgen(js_name = getWaits)]
+    pub fn get_waits(&self, player_idx: usize) -> String {
+        if player_idx >= self.game.player_count() {
+            return String::new();
+        }
+
+        self.game.player(player_idx)
+            .hand
+            .waits()
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// プレイヤーの有効牌の残り枚数の合計を取得
+    #[wasm_bindgen(js_name = getUkeireCount)]
+    pub fn get_ukeire_count(&self, player_idx: usize) -> usize {
+        if player_idx >= self.game.player_count() {
+            return 0;
+        }
+
+        self.game.get_ukeire(player_idx).iter().map(|u| u.remaining).sum()
+    }
+
     /// 山牌の残り枚数を取得
     #[wasm_bindgen(js_name = getWallCount)]
     pub fn get_wall_count(&self) -> usize {
         self.game.get_wall_count()
     }
 
+    /// 現在の進行状態を取得
+    #[wasm_bindgen(js_name = getPhase)]
+    pub fn get_phase(&self) -> String {
+        format!("{:?}", self.game.phase())
+    }
+
     /// ゲームが終了したかチェック
     #[wasm_bindgen(js_name = isGameOver)]
     pub fn is_game_over(&self) -> bool {
         self.game.is_game_over()
     }
 
+    /// 終局後の順位と最終ポイントをJSONで取得
+    #[wasm_bindgen(js_name = getMatchResult)]
+    pub fn get_match_result(&self) -> Option<String> {
+        self.game.match_result().and_then(|result| result.to_json().ok())
+    }
+
+    /// 牌譜（バージョン・シード値・全出来事）をJSONで取得
+    #[wasm_bindgen(js_name = getLog)]
+    pub fn get_log(&self) -> Result<String, JsValue> {
+        self.game.log().to_json().map_err(|e| JsValue::from_str(&e))
+    }
+
     /// 現在のプレイヤーIDを取得
     #[wasm_bindgen(js_name = getCurrentPlayerId)]
     pub fn get_current_player_id(&self) -> usize {
-        self.game.current_player
+        self.game.current_player()
     }
 
     /// チー可能かチェック
@@ -136,19 +206,118 @@ impl WasmGame {
     /// チーを実行
     #[wasm_bindgen(js_name = doChi)]
     pub fn do_chi(&mut self, player_idx: usize, pattern: usize) -> bool {
-        self.game.do_chi(player_idx, pattern)
+        self.game.respond(player_idx, Claim::Chi(pattern))
     }
 
     /// ポンを実行
     #[wasm_bindgen(js_name = doPon)]
     pub fn do_pon(&mut self, player_idx: usize) -> bool {
-        self.game.do_pon(player_idx)
+        self.game.respond(player_idx, Claim::Pon)
     }
 
     /// カンを実行
     #[wasm_bindgen(js_name = doKan)]
     pub fn do_kan(&mut self, player_idx: usize) -> bool {
-        self.game.do_kan(player_idx)
+        self.game.respond(player_idx, Claim::Kan)
+    }
+
+    /// 鳴き・ロンの受付中かどうか
+    #[wasm_bindgen(js_name = isClaimWindowOpen)]
+    pub fn is_claim_window_open(&self) -> bool {
+        self.game.claim_window().is_some()
+    }
+
+    /// プレイヤーが応答待ちかどうか
+    #[wasm_bindgen(js_name = isClaimPending)]
+    pub fn is_claim_pending(&self, player_idx: usize) -> bool {
+        match self.game.claim_window() {
+            Some(window) => window.pending.contains(&player_idx),
+            None => false,
+        }
+    }
+
+    /// 打牌をパス
+    #[wasm_bindgen(js_name = passClaim)]
+    pub fn pass_claim(&mut self, player_idx: usize) -> bool {
+        self.game.respond(player_idx, Claim::Pass)
+    }
+
+    /// 現在のプレイヤーがツモ和了できるか
+    #[wasm_bindgen(js_name = canTsumo)]
+    pub fn can_tsumo(&self) -> bool {
+        self.game.can_tsumo(self.game.current_player())
+    }
+
+    /// 現在のプレイヤーがツモ和了を宣言
+    #[wasm_bindgen(js_name = declareTsumo)]
+    pub fn declare_tsumo(&mut self) -> bool {
+        let current_idx = self.game.current_player();
+        self.game.declare_tsumo(current_idx).is_some()
+    }
+
+    /// 直前の打牌でロン和了できるか
+    #[wasm_bindgen(js_name = canRon)]
+    pub fn can_ron(&self, player_idx: usize) -> bool {
+        player_idx < self.game.player_count() && self.game.can_ron(player_idx)
+    }
+
+    /// ロン和了を宣言（他家の応答と合わせて優先順位で処理される）
+    #[wasm_bindgen(js_name = declareRon)]
+    pub fn declare_ron(&mut self, player_idx: usize) -> bool {
+        self.game.respond(player_idx, Claim::Ron)
+    }
+
+    /// プレイヤーがフリテンかチェック
+    #[wasm_bindgen(js_name = isPlayerFuriten)]
+    pub fn is_player_furiten(&self, player_idx: usize) -> bool {
+        if player_idx < self.game.player_count() {
+            self.game.player(player_idx).is_furiten()
+        } else {
+            false
+        }
+    }
+
+    /// 局の結果を取得（局が終わっていなければ空文字列）
+    #[wasm_bindgen(js_name = getHandResult)]
+    pub fn get_hand_result(&self) -> String {
+        self.game.get_hand_result_string()
+    }
+
+    /// 北抜きできるか（三人麻雀）
+    #[wasm_bindgen(js_name = canKita)]
+    pub fn can_kita(&self) -> bool {
+        self.game.can_kita(self.game.current_player())
+    }
+
+    /// 北を抜きドラにする
+    #[wasm_bindgen(js_name = declareKita)]
+    pub fn declare_kita(&mut self) -> bool {
+        let current_idx = self.game.current_player();
+        self.game.declare_kita(current_idx)
+    }
+
+    /// 九種九牌を宣言できるか
+    #[wasm_bindgen(js_name = canKyuushuKyuuhai)]
+    pub fn can_kyuushu_kyuuhai(&self, player_idx: usize) -> bool {
+        player_idx < self.game.player_count() && self.game.can_kyuushu_kyuuhai(player_idx)
+    }
+
+    /// 九種九牌で流局を宣言
+    #[wasm_bindgen(js_name = declareKyuushuKyuuhai)]
+    pub fn declare_kyuushu_kyuuhai(&mut self, player_idx: usize) -> bool {
+        self.game.declare_kyuushu_kyuuhai(player_idx)
+    }
+
+    /// 次の局へ進む
+    #[wasm_bindgen(js_name = nextHand)]
+    pub fn next_hand(&mut self) -> bool {
+        self.game.next_hand().is_ok()
+    }
+
+    /// 局の表示（例: 東1局 0本場）
+    #[wasm_bindgen(js_name = getRoundString)]
+    pub fn get_round_string(&self) -> String {
+        self.game.get_round_string()
     }
 
     /// CPU（AI）のターンを実行
@@ -159,13 +328,23 @@ impl WasmGame {
             return "山牌がありません".to_string();
         }
 
+        // 北があれば抜く（三人麻雀）
+        let current_idx = self.game.current_player();
+        while !self.game.can_tsumo(current_idx) && self.game.declare_kita(current_idx) {}
+
+        // 和了できればツモ和了
+        if self.game.declare_tsumo(current_idx).is_some() {
+            return "ツモ".to_string();
+        }
+
         // AIで打牌選択
-        let ai = AiEngine::new(AiLevel::Intermediate);
         let hand = &self.game.get_current_player().hand;
+        let legal = self.game.legal_discards(current_idx);
 
-        if let Some(discard_tile) = ai.select_discard(hand) {
+        if let Some(discard_tile) = self.ais[current_idx].select_legal_discard(hand, &legal) {
             let tile_str = discard_tile.to_string();
             self.game.discard_tile(discard_tile);
+            self.auto_respond_cpus();
             tile_str
         } else {
             "打牌できません".to_string()
@@ -176,16 +355,16 @@ impl WasmGame {
     #[wasm_bindgen(js_name = isCurrentPlayerCpu)]
     pub fn is_current_player_cpu(&self) -> bool {
         match self.human_player_index {
-            Some(human_idx) => self.game.current_player != human_idx,
-            None => self.game.current_player != 0, // 通常モード（後方互換性）
+            Some(human_idx) => self.game.current_player() != human_idx,
+            None => self.game.current_player() != 0, // 通常モード（後方互換性）
         }
     }
 
     /// プレイヤーの点数を取得
     #[wasm_bindgen(js_name = getPlayerScore)]
     pub fn get_player_score(&self, player_idx: usize) -> i32 {
-        if player_idx < self.game.players.len() {
-            self.game.players[player_idx].score
+        if player_idx < self.game.player_count() {
+            self.game.player(player_idx).score
         } else {
             0
         }
@@ -194,8 +373,8 @@ impl WasmGame {
     /// プレイヤー名を取得
     #[wasm_bindgen(js_name = getPlayerName)]
     pub fn get_player_name(&self, player_idx: usize) -> String {
-        if player_idx < self.game.players.len() {
-            self.game.players[player_idx].name.clone()
+        if player_idx < self.game.player_count() {
+            self.game.player(player_idx).name.clone()
         } else {
             "Unknown".to_string()
         }
@@ -215,8 +394,8 @@ impl WasmGame {
     /// プレイヤーの河（捨て牌）を取得
     #[wasm_bindgen(js_name = getPlayerDiscards)]
     pub fn get_player_discards(&self, player_idx: usize) -> String {
-        if player_idx < self.game.players.len() {
-            self.game.players[player_idx].get_discards_string()
+        if player_idx < self.game.player_count() {
+            self.game.player(player_idx).get_discards_string()
         } else {
             String::new()
         }
@@ -225,21 +404,21 @@ impl WasmGame {
     /// リーチ可能かチェック
     #[wasm_bindgen(js_name = canRiichi)]
     pub fn can_riichi(&self) -> bool {
-        self.game.get_current_player().can_riichi()
+        self.game.can_riichi(self.game.current_player())
     }
 
     /// リーチを宣言
     #[wasm_bindgen(js_name = declareRiichi)]
     pub fn declare_riichi(&mut self) -> bool {
-        let current_idx = self.game.current_player;
-        self.game.players[current_idx].declare_riichi(self.game.round as usize)
+        let current_idx = self.game.current_player();
+        self.game.declare_riichi(current_idx)
     }
 
     /// プレイヤーがリーチしているかチェック
     #[wasm_bindgen(js_name = isPlayerRiichi)]
     pub fn is_player_riichi(&self, player_idx: usize) -> bool {
-        if player_idx < self.game.players.len() {
-            self.game.players[player_idx].is_riichi
+        if player_idx < self.game.player_count() {
+            self.game.player(player_idx).is_riichi
         } else {
             false
         }
@@ -248,6 +427,26 @@ impl WasmGame {
 
 // ==================== Nostr P2P機能 ====================
 
+#[cfg(feature = "wasm")]
+impl WasmGame {
+    /// CPUの応答（ロンできればロン、それ以外はパス）
+    fn auto_respond_cpus(&mut self) {
+        let human_idx = match self.human_player_index {
+            Some(human_idx) => human_idx,
+            None => return, // 通常モードは全員人間
+        };
+
+        let pending = match self.game.claim_window() {
+            Some(window) => window.pending.clone(),
+            None => return,
+        };
+        for player_idx in pending.into_iter().filter(|&i| i != human_idx) {
+            let claim = if self.game.can_ron(player_idx) { Claim::Ron } else { Claim::Pass };
+            self.game.respond(player_idx, claim);
+        }
+    }
+}
+
 #[cfg(feature = "wasm")]
 use crate::wasm_nostr::{WasmNostrKeys, WasmNostrClient, WasmMatchState, WasmGameEvent, WasmGameEventType};
 